@@ -0,0 +1,1002 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token::spl_token::{self, instruction::AuthorityType};
+use anchor_spl::token::{self, Burn, Mint, MintTo, SetAuthority, SyncNative, Token, TokenAccount};
+use solana_program::program_option::COption;
+
+use crate::fees::ProtocolConfig;
+use crate::governance::{LockAccount, PauseReason, SellPolicy};
+use crate::staking::StakingPool;
+use crate::trading::{self, CircuitBreaker, LiquidityPool, TradingPool};
+
+/// Fixed-point scale used for exponential curve growth factors
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// Launch a new token whose supply is minted and burned along a bonding curve
+pub fn create_curve_token(
+    ctx: Context<CreateCurveToken>,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    curve: CurveKind,
+    graduation_target: GraduationTarget,
+) -> Result<()> {
+    curve.validate()?;
+    graduation_target.validate()?;
+
+    // Fund the SOL vault up to rent exemption so it can receive any later amount
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    system_program::transfer(ctx.accounts.fund_vault_context(), rent_exempt)?;
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.token_mint = ctx.accounts.mint.key();
+    bonding_curve.creator = ctx.accounts.creator.key();
+    bonding_curve.curve = curve;
+    bonding_curve.graduation_target = graduation_target;
+    bonding_curve.graduated = false;
+    bonding_curve.supply = 0;
+    bonding_curve.sol_reserve = 0;
+    bonding_curve.bump = ctx.bumps.bonding_curve;
+    bonding_curve.vault_bump = ctx.bumps.curve_vault;
+    bonding_curve.mint_authority_bump = ctx.bumps.mint_authority;
+
+    msg!(
+        "Curve token created: {} ({}) with {} decimals, mint {}",
+        name,
+        symbol,
+        decimals,
+        bonding_curve.token_mint
+    );
+
+    Ok(())
+}
+
+/// Mint `amount` tokens to the buyer, paying at most `max_amount_in` lamports into the curve
+/// vault before `deadline`
+pub fn buy_from_curve(
+    ctx: Context<BuyFromCurve>,
+    amount: u64,
+    max_amount_in: u64,
+    deadline: i64,
+) -> Result<()> {
+    require_before_deadline(deadline)?;
+    require!(amount > 0, BondingCurveError::ZeroAmount);
+
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    require!(!bonding_curve.graduated, BondingCurveError::CurveGraduated);
+    let unit = token_unit(ctx.accounts.token_mint.decimals)?;
+    let new_supply = bonding_curve
+        .supply
+        .checked_add(amount)
+        .ok_or(BondingCurveError::MathOverflow)?;
+    let cost = bonding_curve
+        .curve
+        .reserve_at(new_supply, unit)?
+        .checked_sub(bonding_curve.curve.reserve_at(bonding_curve.supply, unit)?)
+        .ok_or(BondingCurveError::MathOverflow)?;
+    let cost = u64::try_from(cost).map_err(|_| BondingCurveError::MathOverflow)?;
+    require!(cost > 0, BondingCurveError::ZeroAmount);
+    require!(cost <= max_amount_in, BondingCurveError::SlippageExceeded);
+
+    let seeds = &[b"mint_authority".as_ref(), &[bonding_curve.mint_authority_bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    system_program::transfer(ctx.accounts.payment_context(), cost)?;
+    token::mint_to(
+        ctx.accounts.mint_to_context().with_signer(signer_seeds),
+        amount,
+    )?;
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.supply = new_supply;
+    bonding_curve.sol_reserve = bonding_curve
+        .sol_reserve
+        .checked_add(cost)
+        .ok_or(BondingCurveError::MathOverflow)?;
+
+    msg!(
+        "User {} bought {} curve tokens for {} lamports",
+        ctx.accounts.buyer.key(),
+        amount,
+        cost
+    );
+
+    Ok(())
+}
+
+/// Burn `amount` of the seller's tokens and refund their curve value, at least `min_amount_out`
+/// lamports, from the curve vault before `deadline`
+pub fn sell_to_curve(
+    ctx: Context<SellToCurve>,
+    amount: u64,
+    min_amount_out: u64,
+    deadline: i64,
+) -> Result<()> {
+    require_before_deadline(deadline)?;
+    require!(amount > 0, BondingCurveError::ZeroAmount);
+    require!(
+        ctx.accounts.seller_token_account.amount >= amount,
+        BondingCurveError::InsufficientBalance
+    );
+
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    require!(!bonding_curve.graduated, BondingCurveError::CurveGraduated);
+    let unit = token_unit(ctx.accounts.token_mint.decimals)?;
+    let new_supply = bonding_curve
+        .supply
+        .checked_sub(amount)
+        .ok_or(BondingCurveError::InsufficientBalance)?;
+    let refund = bonding_curve
+        .curve
+        .reserve_at(bonding_curve.supply, unit)?
+        .checked_sub(bonding_curve.curve.reserve_at(new_supply, unit)?)
+        .ok_or(BondingCurveError::MathOverflow)?;
+    let refund = u64::try_from(refund).map_err(|_| BondingCurveError::MathOverflow)?;
+    require!(
+        refund <= bonding_curve.sol_reserve,
+        BondingCurveError::InsufficientReserve
+    );
+    require!(
+        refund >= min_amount_out,
+        BondingCurveError::SlippageExceeded
+    );
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"curve_vault", mint_key.as_ref(), &[bonding_curve.vault_bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::burn(ctx.accounts.burn_context(), amount)?;
+    system_program::transfer(
+        ctx.accounts.refund_context().with_signer(signer_seeds),
+        refund,
+    )?;
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.supply = new_supply;
+    bonding_curve.sol_reserve -= refund;
+
+    msg!(
+        "User {} sold {} curve tokens for {} lamports",
+        ctx.accounts.seller.key(),
+        amount,
+        refund
+    );
+
+    Ok(())
+}
+
+/// Freeze a curve that reached its target and move its SOL, plus tokens minted at the final
+/// curve price, into a new liquidity pool whose shares are permanently locked; callable by anyone.
+/// Also creates the lock account and staking pool that pool sells need, if the creator has not
+pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    require!(!bonding_curve.graduated, BondingCurveError::CurveGraduated);
+
+    let unit = token_unit(ctx.accounts.token_mint.decimals)?;
+    let spot_price = bonding_curve.curve.spot_price(bonding_curve.supply, unit)?;
+    require!(
+        bonding_curve.target_reached(spot_price, unit)?,
+        BondingCurveError::GraduationTargetNotReached
+    );
+
+    // Seed the pool at the curve's closing price
+    let sol_amount = bonding_curve.sol_reserve;
+    let token_amount = u64::try_from(
+        (sol_amount as u128)
+            .checked_mul(unit)
+            .ok_or(BondingCurveError::MathOverflow)?
+            / spot_price,
+    )
+    .map_err(|_| BondingCurveError::MathOverflow)?;
+    require!(
+        sol_amount > 0 && token_amount > 0,
+        BondingCurveError::ZeroAmount
+    );
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let vault_seeds = &[b"curve_vault", mint_key.as_ref(), &[bonding_curve.vault_bump]];
+    let authority_seeds = &[b"mint_authority".as_ref(), &[bonding_curve.mint_authority_bump]];
+
+    // Wrap the collected SOL into the pool's quote vault
+    system_program::transfer(
+        ctx.accounts.wrap_sol_context().with_signer(&[&vault_seeds[..]]),
+        sol_amount,
+    )?;
+    token::sync_native(ctx.accounts.sync_native_context())?;
+
+    // Mint the pool's token side, then give up minting for good
+    token::mint_to(
+        ctx.accounts.mint_to_context().with_signer(&[&authority_seeds[..]]),
+        token_amount,
+    )?;
+    token::set_authority(
+        ctx.accounts.set_authority_context().with_signer(&[&authority_seeds[..]]),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    let trading_pool = &mut ctx.accounts.trading_pool;
+    trading_pool.token_mint = mint_key;
+    trading_pool.authority = ctx.accounts.bonding_curve.creator;
+    trading_pool.total_traded = 0;
+    trading_pool.creator_fee_bps = 0;
+    trading_pool.bump = ctx.bumps.trading_pool;
+
+    // Shares are recorded without minting LP tokens, so no one can ever withdraw this liquidity
+    let liquidity_pool = &mut ctx.accounts.liquidity_pool;
+    liquidity_pool.token_mint = mint_key;
+    liquidity_pool.quote_mint = ctx.accounts.quote_mint.key();
+    liquidity_pool.lp_mint = ctx.accounts.lp_mint.key();
+    liquidity_pool.token_reserve = token_amount;
+    liquidity_pool.quote_reserve = sol_amount;
+    liquidity_pool.total_shares =
+        trading::isqrt(token_amount as u128 * sol_amount as u128) as u64;
+    liquidity_pool.lp_fee_bps = trading::DEFAULT_LP_FEE_BPS;
+    liquidity_pool.accrued_lp_fees = 0;
+    liquidity_pool.price_cumulative = 0;
+    liquidity_pool.last_price_update = Clock::get()?.unix_timestamp;
+    liquidity_pool.observations = Vec::new();
+    liquidity_pool.bump = ctx.bumps.liquidity_pool;
+
+    // Pool sells go through the lock account and pay their tax to the staking pool; create
+    // whichever of them the creator has not set up yet, with no sell limits or stake minimum
+    let lock_account = &mut ctx.accounts.lock_account;
+    if lock_account.token_mint == Pubkey::default() {
+        lock_account.token_mint = mint_key;
+        lock_account.bump = ctx.bumps.lock_account;
+        lock_account.is_locked = false;
+        lock_account.lock_start_time = 0;
+        lock_account.lock_duration = 0;
+        lock_account.sell_policy = SellPolicy::default();
+        lock_account.current_slot = 0;
+        lock_account.sold_in_slot = 0;
+        lock_account.lock_vote_pending_until = 0;
+        lock_account.circuit_breaker = CircuitBreaker::default();
+        lock_account.paused_until = 0;
+        lock_account.pause_reason = PauseReason::None;
+    }
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    if staking_pool.token_mint == Pubkey::default() {
+        staking_pool.token_mint = mint_key;
+        staking_pool.authority = ctx.accounts.bonding_curve.creator;
+        staking_pool.total_staked = 0;
+        staking_pool.min_stake_duration = 0;
+        staking_pool.reward_per_token = 0;
+        staking_pool.undistributed_rewards = 0;
+        staking_pool.bump = ctx.bumps.staking_pool;
+    }
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.graduated = true;
+    bonding_curve.sol_reserve = 0;
+
+    msg!(
+        "Curve for mint {} graduated with {} lamports and {} tokens; {} pool shares locked",
+        mint_key,
+        sol_amount,
+        token_amount,
+        liquidity_pool.total_shares
+    );
+
+    Ok(())
+}
+
+/// Whether `authority` may set up governance, staking and trading for `token_mint`: its mint
+/// authority, or the creator of its bonding curve once the program holds (or gave up) minting
+pub fn is_launch_authority(
+    token_mint: &Mint,
+    bonding_curve: Option<&BondingCurve>,
+    authority: Pubkey,
+) -> bool {
+    token_mint.mint_authority == COption::Some(authority)
+        || bonding_curve.is_some_and(|curve| curve.creator == authority)
+}
+
+/// Whether `token_mint` is still minted by a bonding curve that has not graduated; the trading
+/// and liquidity pools of such a mint are created by `graduate`
+pub fn is_curve_minting(token_mint: &Mint) -> bool {
+    let (mint_authority, _) = Pubkey::find_program_address(&[b"mint_authority"], &crate::ID);
+    token_mint.mint_authority == COption::Some(mint_authority)
+}
+
+/// Reject a trade submitted after its deadline
+fn require_before_deadline(deadline: i64) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp <= deadline,
+        BondingCurveError::DeadlineExpired
+    );
+    Ok(())
+}
+
+/// Number of base units in one whole token
+pub(crate) fn token_unit(decimals: u8) -> Result<u128> {
+    Ok(10u128
+        .checked_pow(decimals as u32)
+        .ok_or(BondingCurveError::MathOverflow)?)
+}
+
+/// `base^exp` for a WAD-scaled `base`, by repeated squaring
+fn wad_pow(mut base: u128, mut exp: u64) -> Result<u128> {
+    let mut result = WAD;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = wad_mul(result, base)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = wad_mul(base, base)?;
+        }
+    }
+    Ok(result)
+}
+
+fn wad_mul(a: u128, b: u128) -> Result<u128> {
+    Ok(a.checked_mul(b).ok_or(BondingCurveError::MathOverflow)? / WAD)
+}
+
+/// **Context for Creating a Bonding-Curve Token**
+#[derive(Accounts)]
+#[instruction(name: String, symbol: String, decimals: u8)]
+pub struct CreateCurveToken<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// CHECK: PDA that signs as mint authority for every curve token
+    #[account(seeds = [b"mint_authority"], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = decimals,
+        mint::authority = mint_authority,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + BondingCurve::INIT_SPACE,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [b"curve_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub curve_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// **Context for Graduating a Bonding Curve**
+#[derive(Accounts)]
+pub struct Graduate<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: PDA that signs as mint authority for every curve token
+    #[account(seeds = [b"mint_authority"], bump = bonding_curve.mint_authority_bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(address = spl_token::native_mint::ID @ BondingCurveError::InvalidQuoteMint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", token_mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [b"curve_vault", token_mint.key().as_ref()],
+        bump = bonding_curve.vault_bump
+    )]
+    pub curve_vault: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TradingPool::INIT_SPACE,
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LiquidityPool::INIT_SPACE,
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
+        bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_token_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = liquidity_pool
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_quote_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = liquidity_pool
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"lp_mint", token_mint.key().as_ref()],
+        bump,
+        mint::decimals = trading::LP_MINT_DECIMALS,
+        mint::authority = liquidity_pool
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"protocol_treasury", quote_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = protocol_config
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"creator_fee_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = liquidity_pool
+    )]
+    pub creator_fee_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + LockAccount::INIT_SPACE,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + StakingPool::INIT_SPACE,
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = staking_pool
+    )]
+    pub staking_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"reward_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = staking_pool
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// **Context for Buying from a Bonding Curve**
+#[derive(Accounts)]
+pub struct BuyFromCurve<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: PDA that signs as mint authority for every curve token
+    #[account(seeds = [b"mint_authority"], bump = bonding_curve.mint_authority_bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", token_mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [b"curve_vault", token_mint.key().as_ref()],
+        bump = bonding_curve.vault_bump
+    )]
+    pub curve_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == token_mint.key()
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Context for Selling to a Bonding Curve**
+#[derive(Accounts)]
+pub struct SellToCurve<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(mut)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", token_mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [b"curve_vault", token_mint.key().as_ref()],
+        bump = bonding_curve.vault_bump
+    )]
+    pub curve_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == token_mint.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Bonding Curve Account (one per curve-launched mint)**
+#[account]
+#[derive(InitSpace)]
+pub struct BondingCurve {
+    pub token_mint: Pubkey,
+    pub creator: Pubkey,
+    pub curve: CurveKind,
+    pub graduation_target: GraduationTarget,
+    pub graduated: bool,           // Curve is frozen and its liquidity lives in the pool
+    pub supply: u64,               // Tokens currently minted through the curve
+    pub sol_reserve: u64,          // Lamports backing `supply`, excluding vault rent
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub mint_authority_bump: u8,
+}
+
+impl BondingCurve {
+    /// Whether the curve has reached the point at which it can graduate
+    pub fn target_reached(&self, spot_price: u128, unit: u128) -> Result<bool> {
+        Ok(match self.graduation_target {
+            GraduationTarget::SolReserve { lamports } => self.sol_reserve >= lamports,
+            GraduationTarget::MarketCap { lamports } => {
+                spot_price
+                    .checked_mul(self.supply as u128)
+                    .ok_or(BondingCurveError::MathOverflow)?
+                    / unit
+                    >= lamports as u128
+            }
+        })
+    }
+}
+
+/// **Graduation Target (in lamports)**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum GraduationTarget {
+    SolReserve { lamports: u64 },
+    MarketCap { lamports: u64 },
+}
+
+impl GraduationTarget {
+    /// Reject targets that would let a curve graduate before anyone bought
+    pub fn validate(&self) -> Result<()> {
+        let (GraduationTarget::SolReserve { lamports } | GraduationTarget::MarketCap { lamports }) =
+            *self;
+        require!(lamports > 0, BondingCurveError::InvalidGraduationTarget);
+        Ok(())
+    }
+}
+
+/// **Curve Shape (price as a function of curve supply)**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum CurveKind {
+    /// Price per whole token is `base_price + slope * supply` (supply in whole tokens)
+    Linear { base_price: u64, slope: u64 },
+    /// Price per whole token grows by `growth_bps` every `step_size` base units sold
+    Exponential {
+        base_price: u64,
+        growth_bps: u16,
+        step_size: u64,
+    },
+    /// Price follows `x * y = k` over virtual SOL and token reserves
+    VirtualReserves {
+        virtual_sol_reserve: u64,
+        virtual_token_reserve: u64,
+    },
+}
+
+impl CurveKind {
+    /// Reject curves that cannot price a trade
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            CurveKind::Linear { base_price, slope } => {
+                require!(
+                    base_price > 0 || slope > 0,
+                    BondingCurveError::InvalidCurve
+                );
+            }
+            CurveKind::Exponential {
+                base_price,
+                growth_bps,
+                step_size,
+            } => {
+                require!(
+                    base_price > 0 && growth_bps > 0 && step_size > 0,
+                    BondingCurveError::InvalidCurve
+                );
+            }
+            CurveKind::VirtualReserves {
+                virtual_sol_reserve,
+                virtual_token_reserve,
+            } => {
+                require!(
+                    virtual_sol_reserve > 0 && virtual_token_reserve > 0,
+                    BondingCurveError::InvalidCurve
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Price of one whole token, in lamports, at the given curve supply
+    pub fn spot_price(&self, supply: u64, unit: u128) -> Result<u128> {
+        let supply = supply as u128;
+        let price = match *self {
+            CurveKind::Linear { base_price, slope } => {
+                (base_price as u128)
+                    + (slope as u128)
+                        .checked_mul(supply)
+                        .ok_or(BondingCurveError::MathOverflow)?
+                        / unit
+            }
+            CurveKind::Exponential {
+                base_price,
+                growth_bps,
+                step_size,
+            } => {
+                let growth = WAD * (10_000 + growth_bps as u128) / 10_000;
+                let factor = wad_pow(growth, (supply / step_size as u128) as u64)?;
+                wad_mul(base_price as u128, factor)?
+            }
+            CurveKind::VirtualReserves {
+                virtual_sol_reserve,
+                virtual_token_reserve,
+            } => {
+                let tokens = virtual_token_reserve as u128;
+                require!(supply < tokens, BondingCurveError::CurveSupplyExhausted);
+                let sol = virtual_sol_reserve as u128 * tokens / (tokens - supply);
+                sol.checked_mul(unit)
+                    .ok_or(BondingCurveError::MathOverflow)?
+                    / (tokens - supply)
+            }
+        };
+        require!(price > 0, BondingCurveError::InvalidCurve);
+        Ok(price)
+    }
+
+    /// Lamports needed to mint `supply` base units from zero; buys and sells pay the difference,
+    /// so the vault always holds exactly the reserve for the current supply
+    pub fn reserve_at(&self, supply: u64, unit: u128) -> Result<u128> {
+        let supply = supply as u128;
+        match *self {
+            CurveKind::Linear { base_price, slope } => {
+                // base_price * s + slope * s^2 / 2, with s in whole tokens
+                let base = (base_price as u128)
+                    .checked_mul(supply)
+                    .ok_or(BondingCurveError::MathOverflow)?
+                    / unit;
+                let growth = ((slope as u128)
+                    .checked_mul(supply)
+                    .ok_or(BondingCurveError::MathOverflow)?
+                    / unit)
+                    .checked_mul(supply)
+                    .ok_or(BondingCurveError::MathOverflow)?
+                    / (2 * unit);
+                Ok(base
+                    .checked_add(growth)
+                    .ok_or(BondingCurveError::MathOverflow)?)
+            }
+            CurveKind::Exponential {
+                base_price,
+                growth_bps,
+                step_size,
+            } => {
+                // Whole steps form a geometric series; the partial step is priced at the current step
+                let step_size = step_size as u128;
+                let steps = (supply / step_size) as u64;
+                let remainder = supply % step_size;
+                let growth = WAD * (10_000 + growth_bps as u128) / 10_000;
+                let factor = wad_pow(growth, steps)?;
+
+                let series = (factor - WAD)
+                    .checked_mul(WAD)
+                    .ok_or(BondingCurveError::MathOverflow)?
+                    / (growth - WAD);
+                let units = wad_mul(series, step_size)?
+                    .checked_add(wad_mul(factor, remainder)?)
+                    .ok_or(BondingCurveError::MathOverflow)?;
+                Ok((base_price as u128)
+                    .checked_mul(units)
+                    .ok_or(BondingCurveError::MathOverflow)?
+                    / unit)
+            }
+            CurveKind::VirtualReserves {
+                virtual_sol_reserve,
+                virtual_token_reserve,
+            } => {
+                // SOL that must enter the virtual pool to take `supply` tokens out of it
+                let sol = virtual_sol_reserve as u128;
+                let tokens = virtual_token_reserve as u128;
+                require!(supply < tokens, BondingCurveError::CurveSupplyExhausted);
+                Ok(sol * tokens / (tokens - supply) - sol)
+            }
+        }
+    }
+}
+
+/// **Bonding Curve Errors (codes 6500-6599)**
+#[error_code(offset = 6500)]
+pub enum BondingCurveError {
+    #[msg("Curve parameters cannot price a trade.")]
+    InvalidCurve,
+    #[msg("Amount is too small to trade on the curve.")]
+    ZeroAmount,
+    #[msg("Insufficient balance to sell tokens.")]
+    InsufficientBalance,
+    #[msg("Curve vault holds too little SOL for this sale.")]
+    InsufficientReserve,
+    #[msg("Purchase would exceed the curve's virtual token reserve.")]
+    CurveSupplyExhausted,
+    #[msg("Arithmetic overflow in bonding curve calculation.")]
+    MathOverflow,
+    #[msg("Graduation target must be greater than zero.")]
+    InvalidGraduationTarget,
+    #[msg("Bonding curve has not reached its graduation target.")]
+    GraduationTargetNotReached,
+    #[msg("Bonding curve has graduated; trade in the liquidity pool instead.")]
+    CurveGraduated,
+    #[msg("Graduated pools are paired with wrapped SOL.")]
+    InvalidQuoteMint,
+    #[msg("Trade price moved beyond the allowed slippage.")]
+    SlippageExceeded,
+    #[msg("Trade deadline has passed.")]
+    DeadlineExpired,
+}
+
+impl<'info> CreateCurveToken<'info> {
+    fn fund_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.creator.to_account_info(),
+            to: self.curve_vault.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> Graduate<'info> {
+    fn wrap_sol_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.curve_vault.to_account_info(),
+            to: self.quote_vault.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+
+    fn sync_native_context(&self) -> CpiContext<'_, '_, '_, 'info, SyncNative<'info>> {
+        let cpi_accounts = SyncNative {
+            account: self.quote_vault.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn mint_to_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.token_mint.to_account_info(),
+            to: self.token_vault.to_account_info(),
+            authority: self.mint_authority.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn set_authority_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        let cpi_accounts = SetAuthority {
+            current_authority: self.mint_authority.to_account_info(),
+            account_or_mint: self.token_mint.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> BuyFromCurve<'info> {
+    fn payment_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.buyer.to_account_info(),
+            to: self.curve_vault.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+
+    fn mint_to_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.token_mint.to_account_info(),
+            to: self.buyer_token_account.to_account_info(),
+            authority: self.mint_authority.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> SellToCurve<'info> {
+    fn burn_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.token_mint.to_account_info(),
+            from: self.seller_token_account.to_account_info(),
+            authority: self.seller.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn refund_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.curve_vault.to_account_info(),
+            to: self.seller.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIT: u128 = 1_000_000;
+
+    fn curves() -> [CurveKind; 3] {
+        [
+            CurveKind::Linear {
+                base_price: 1_000,
+                slope: 10,
+            },
+            CurveKind::Exponential {
+                base_price: 1_000,
+                growth_bps: 100,
+                step_size: 1_000_000_000,
+            },
+            CurveKind::VirtualReserves {
+                virtual_sol_reserve: 30_000_000_000,
+                virtual_token_reserve: 1_000_000_000_000_000,
+            },
+        ]
+    }
+
+    /// Lamports the curve charges to move its supply from `from` to `to`
+    fn cost(curve: &CurveKind, from: u64, to: u64) -> u128 {
+        curve.reserve_at(to, UNIT).unwrap() - curve.reserve_at(from, UNIT).unwrap()
+    }
+
+    #[test]
+    fn sells_refund_what_buys_paid() {
+        for curve in curves() {
+            curve.validate().unwrap();
+            assert_eq!(curve.reserve_at(0, UNIT).unwrap(), 0);
+
+            // Many small buys, then one sell of everything, returns exactly what was paid
+            let mut supply = 0;
+            let mut paid = 0;
+            for amount in [1, 999, 1_000_000, 123_456_789, 5_000_000_000] {
+                paid += cost(&curve, supply, supply + amount);
+                supply += amount;
+            }
+            assert_eq!(cost(&curve, 0, supply), paid);
+        }
+    }
+
+    #[test]
+    fn buys_never_get_cheaper() {
+        for curve in curves() {
+            let mut previous = 0;
+            for supply in (0..20).map(|step| step * 750_000_000) {
+                let reserve = curve.reserve_at(supply, UNIT).unwrap();
+                assert!(reserve >= previous);
+                previous = reserve;
+
+                let price = curve.spot_price(supply, UNIT).unwrap();
+                assert!(price <= curve.spot_price(supply + 750_000_000, UNIT).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn virtual_reserves_cannot_be_exhausted() {
+        let curve = CurveKind::VirtualReserves {
+            virtual_sol_reserve: 1_000,
+            virtual_token_reserve: 1_000,
+        };
+        assert_eq!(
+            curve.reserve_at(1_000, UNIT).unwrap_err(),
+            BondingCurveError::CurveSupplyExhausted.into()
+        );
+        assert_eq!(
+            curve.spot_price(1_000, UNIT).unwrap_err(),
+            BondingCurveError::CurveSupplyExhausted.into()
+        );
+    }
+
+    #[test]
+    fn invalid_curves_are_rejected() {
+        for curve in [
+            CurveKind::Linear {
+                base_price: 0,
+                slope: 0,
+            },
+            CurveKind::Exponential {
+                base_price: 1_000,
+                growth_bps: 0,
+                step_size: 1,
+            },
+            CurveKind::VirtualReserves {
+                virtual_sol_reserve: 0,
+                virtual_token_reserve: 1,
+            },
+        ] {
+            assert_eq!(
+                curve.validate().unwrap_err(),
+                BondingCurveError::InvalidCurve.into()
+            );
+        }
+    }
+}
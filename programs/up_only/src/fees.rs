@@ -0,0 +1,400 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::governance::BPS_DENOMINATOR;
+use crate::trading::{LiquidityPool, TradingPool};
+
+/// Highest protocol, creator or LP fee that can be configured (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Create the program-wide fee configuration; the payer becomes the fee admin
+pub fn initialize_protocol_config(
+    ctx: Context<InitializeProtocolConfig>,
+    protocol_fee_bps: u16,
+) -> Result<()> {
+    validate_fee(protocol_fee_bps)?;
+
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    protocol_config.admin = ctx.accounts.admin.key();
+    protocol_config.protocol_fee_bps = protocol_fee_bps;
+    protocol_config.bump = ctx.bumps.protocol_config;
+
+    msg!(
+        "Protocol config initialized by {} with a {} bps protocol fee",
+        protocol_config.admin,
+        protocol_fee_bps
+    );
+
+    Ok(())
+}
+
+/// Change the protocol fee charged on every pool trade
+pub fn set_protocol_fee(ctx: Context<SetProtocolFee>, protocol_fee_bps: u16) -> Result<()> {
+    validate_fee(protocol_fee_bps)?;
+    ctx.accounts.protocol_config.protocol_fee_bps = protocol_fee_bps;
+
+    msg!("Protocol fee set to {} bps", protocol_fee_bps);
+
+    Ok(())
+}
+
+/// Change the creator fee charged on trades of one mint
+pub fn set_creator_fee(ctx: Context<SetCreatorFee>, creator_fee_bps: u16) -> Result<()> {
+    validate_fee(creator_fee_bps)?;
+    ctx.accounts.trading_pool.creator_fee_bps = creator_fee_bps;
+
+    msg!(
+        "Creator fee for mint {} set to {} bps",
+        ctx.accounts.token_mint.key(),
+        creator_fee_bps
+    );
+
+    Ok(())
+}
+
+/// Change the fee on pool trades of one mint that stays in the pool for its liquidity providers
+pub fn set_lp_fee(ctx: Context<SetLpFee>, lp_fee_bps: u16) -> Result<()> {
+    validate_fee(lp_fee_bps)?;
+    ctx.accounts.liquidity_pool.lp_fee_bps = lp_fee_bps;
+
+    msg!(
+        "LP fee for mint {} set to {} bps",
+        ctx.accounts.token_mint.key(),
+        lp_fee_bps
+    );
+
+    Ok(())
+}
+
+/// Send every creator fee accrued for a mint to the creator
+pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let amount = ctx.accounts.creator_fee_vault.amount;
+    require!(amount > 0, FeeError::NoFeesToClaim);
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"liquidity_pool", mint_key.as_ref(), &[ctx.accounts.liquidity_pool.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        ctx.accounts.transfer_context().with_signer(signer_seeds),
+        amount,
+    )?;
+
+    msg!(
+        "Creator {} claimed {} in fees for mint {}",
+        ctx.accounts.creator.key(),
+        amount,
+        mint_key
+    );
+
+    Ok(())
+}
+
+/// Move `amount` of accrued protocol fees out of the treasury for one quote mint
+pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+    require!(amount > 0, FeeError::NoFeesToClaim);
+    require!(
+        ctx.accounts.treasury_vault.amount >= amount,
+        FeeError::InsufficientFees
+    );
+
+    let seeds = &[b"protocol_config".as_ref(), &[ctx.accounts.protocol_config.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        ctx.accounts.transfer_context().with_signer(signer_seeds),
+        amount,
+    )?;
+
+    msg!(
+        "Admin {} withdrew {} protocol fees in quote mint {}",
+        ctx.accounts.admin.key(),
+        amount,
+        ctx.accounts.quote_mint.key()
+    );
+
+    Ok(())
+}
+
+/// Ensure a fee stays within `MAX_FEE_BPS`
+pub(crate) fn validate_fee(fee_bps: u16) -> Result<()> {
+    require!(fee_bps <= MAX_FEE_BPS, FeeError::FeeTooHigh);
+    Ok(())
+}
+
+/// **Protocol, creator and liquidity provider shares of one trade, in quote units**
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TradeFees {
+    pub protocol: u64,
+    pub creator: u64,
+    pub liquidity: u64,            // Left in the pool reserves, accruing to LP token holders
+}
+
+impl TradeFees {
+    /// Fees charged on a gross quote `amount`, each rounded down
+    pub fn on_gross(
+        amount: u64,
+        protocol_fee_bps: u16,
+        creator_fee_bps: u16,
+        lp_fee_bps: u16,
+    ) -> Result<Self> {
+        Ok(Self {
+            protocol: bps_of(amount, protocol_fee_bps)?,
+            creator: bps_of(amount, creator_fee_bps)?,
+            liquidity: bps_of(amount, lp_fee_bps)?,
+        })
+    }
+
+    /// Smallest gross quote amount that still leaves `net` after fees, with its fees; the
+    /// rounding remainder goes to the creator
+    pub fn gross_up(
+        net: u64,
+        protocol_fee_bps: u16,
+        creator_fee_bps: u16,
+        lp_fee_bps: u16,
+    ) -> Result<(u64, Self)> {
+        let fee_bps = protocol_fee_bps + creator_fee_bps + lp_fee_bps;
+        let gross = u64::try_from(
+            (net as u128 * BPS_DENOMINATOR as u128)
+                .div_ceil((BPS_DENOMINATOR - fee_bps) as u128),
+        )
+        .map_err(|_| FeeError::MathOverflow)?;
+        let protocol = bps_of(gross, protocol_fee_bps)?;
+        let liquidity = bps_of(gross, lp_fee_bps)?;
+        let fees = Self {
+            protocol,
+            creator: gross - net - protocol - liquidity,
+            liquidity,
+        };
+        Ok((gross, fees))
+    }
+
+    pub fn total(&self) -> u64 {
+        self.protocol + self.creator + self.liquidity
+    }
+}
+
+fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    Ok(u64::try_from(amount as u128 * bps as u128 / BPS_DENOMINATOR as u128)
+        .map_err(|_| FeeError::MathOverflow)?)
+}
+
+/// **Context for Initializing the Protocol Config**
+#[derive(Accounts)]
+pub struct InitializeProtocolConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ProtocolConfig::INIT_SPACE,
+        seeds = [b"protocol_config"],
+        bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// **Context for Setting the Protocol Fee**
+#[derive(Accounts)]
+pub struct SetProtocolFee<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ FeeError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+/// **Context for Setting a Creator Fee**
+#[derive(Accounts)]
+pub struct SetCreatorFee<'info> {
+    pub authority: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump = trading_pool.bump,
+        has_one = authority @ FeeError::Unauthorized
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+}
+
+/// **Context for Setting an LP Fee**
+#[derive(Accounts)]
+pub struct SetLpFee<'info> {
+    pub authority: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump = trading_pool.bump,
+        has_one = authority @ FeeError::Unauthorized
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
+        bump = liquidity_pool.bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+}
+
+/// **Context for Claiming Creator Fees**
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    pub creator: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump = trading_pool.bump,
+        constraint = trading_pool.authority == creator.key() @ FeeError::Unauthorized
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
+        bump = liquidity_pool.bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_quote_account.mint == liquidity_pool.quote_mint
+    )]
+    pub creator_quote_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Context for Withdrawing Protocol Fees**
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ FeeError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_treasury", quote_mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == quote_mint.key()
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Protocol Fee Configuration (one per program)**
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,             // Sets the protocol fee and withdraws the treasury
+    pub protocol_fee_bps: u16,     // Share of every pool trade sent to the treasury
+    pub bump: u8,
+}
+
+/// **Fee Errors (codes 6300-6399)**
+#[error_code(offset = 6300)]
+pub enum FeeError {
+    #[msg("Fee exceeds the maximum of 1000 bps.")]
+    FeeTooHigh,
+    #[msg("Only the fee admin or token creator can perform this action.")]
+    Unauthorized,
+    #[msg("No fees available to withdraw.")]
+    NoFeesToClaim,
+    #[msg("Treasury holds fewer fees than requested.")]
+    InsufficientFees,
+    #[msg("Arithmetic overflow in fee calculation.")]
+    MathOverflow,
+}
+
+impl<'info> ClaimCreatorFees<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.creator_fee_vault.to_account_info(),
+            to: self.creator_quote_account.to_account_info(),
+            authority: self.liquidity_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> WithdrawProtocolFees<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.treasury_vault.to_account_info(),
+            to: self.destination.to_account_info(),
+            authority: self.protocol_config.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn on_gross_rounds_each_fee_down() {
+        let fees = TradeFees::on_gross(9_999, 100, 50, 30).unwrap();
+        assert_eq!(fees.protocol, 99);
+        assert_eq!(fees.creator, 49);
+        assert_eq!(fees.liquidity, 29);
+        assert_eq!(fees.total(), 177);
+    }
+
+    #[test]
+    fn gross_up_leaves_exactly_net() {
+        for net in [1, 7, 999, 10_000, 123_456_789] {
+            for (protocol_bps, creator_bps, lp_bps) in [(0, 0, 0), (100, 50, 30), (333, 0, 1)] {
+                let (gross, fees) =
+                    TradeFees::gross_up(net, protocol_bps, creator_bps, lp_bps).unwrap();
+                assert_eq!(gross - fees.total(), net);
+
+                // Protocol and LP fees match what `on_gross` charges on the same amount
+                let charged = TradeFees::on_gross(gross, protocol_bps, creator_bps, lp_bps).unwrap();
+                assert_eq!(fees.protocol, charged.protocol);
+                assert_eq!(fees.liquidity, charged.liquidity);
+                assert!(fees.creator >= charged.creator);
+
+                // One unit less would not cover the combined fee rate
+                let fee_bps = (protocol_bps + creator_bps + lp_bps) as u128;
+                assert!(
+                    (gross as u128 - 1) * (BPS_DENOMINATOR as u128 - fee_bps)
+                        < net as u128 * BPS_DENOMINATOR as u128
+                );
+            }
+        }
+    }
+}
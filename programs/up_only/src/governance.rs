@@ -1,94 +1,771 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
 use solana_program::clock::Clock;
 
-#[program]
-pub mod governance {
-    use super::*;
+use crate::bonding_curve::BondingCurve;
+use crate::staking::{StakerAccount, StakingPool};
+use crate::trading::CircuitBreaker;
 
-    /// Cast a vote in favor or against a proposal
-    pub fn cast_vote(ctx: Context<CastVote>, in_favor: bool) -> Result<()> {
-        let governance_account = &mut ctx.accounts.governance_account;
+/// Time after voting ends during which a succeeded proposal can be executed (3 days)
+pub const PROPOSAL_EXECUTION_WINDOW: i64 = 259200;
 
-        if in_favor {
-            governance_account.votes_in_favor += 1;
-        } else {
-            governance_account.votes_against += 1;
-        }
+/// Seconds in one week, the unit lock proposals are expressed in
+pub const SECONDS_PER_WEEK: i64 = 604800;
 
-        msg!(
-            "Vote Casted: In Favor: {}, Against: {}",
-            governance_account.votes_in_favor,
-            governance_account.votes_against
-        );
+/// Basis-point denominator used by all governance thresholds (100%)
+pub const BPS_DENOMINATOR: u16 = 10_000;
 
-        Ok(())
-    }
+/// Longest voting delay or voting period governance may allow (30 days)
+pub const MAX_VOTING_DURATION: i64 = 2_592_000;
+
+/// Longest per-wallet sell window or sell cooldown a sell policy may set (30 days)
+pub const MAX_SELL_PERIOD: i64 = 2_592_000;
+
+/// Create the per-mint governance account with its voting parameters
+pub fn initialize_governance(
+    ctx: Context<InitializeGovernance>,
+    approval_threshold_bps: u16,
+    quorum_threshold_bps: u16,
+    quorum_base: QuorumBase,
+    min_lock_time: i64,
+    max_lock_time: i64,
+    voting_rules: VotingRules,
+) -> Result<()> {
+    validate_thresholds(approval_threshold_bps, quorum_threshold_bps)?;
+    validate_lock_bounds(min_lock_time, max_lock_time)?;
+    voting_rules.validate()?;
+
+    let governance_account = &mut ctx.accounts.governance_account;
+    governance_account.token_mint = ctx.accounts.token_mint.key();
+    governance_account.authority = ctx.accounts.authority.key();
+    governance_account.bump = ctx.bumps.governance_account;
+    governance_account.proposal_count = 0;
+    governance_account.total_supply = ctx.accounts.token_mint.supply;
+    governance_account.approval_threshold_bps = approval_threshold_bps;
+    governance_account.quorum_threshold_bps = quorum_threshold_bps;
+    governance_account.quorum_base = quorum_base;
+    governance_account.min_lock_time = min_lock_time;
+    governance_account.max_lock_time = max_lock_time;
+    governance_account.voting_rules = voting_rules;
+
+    msg!(
+        "Governance initialized for mint {} (approval: {} bps, quorum: {} bps, lock: {}-{} seconds)",
+        governance_account.token_mint,
+        approval_threshold_bps,
+        quorum_threshold_bps,
+        min_lock_time,
+        max_lock_time
+    );
+
+    Ok(())
+}
+
+/// Create the per-mint lock account in its unlocked state with an initial sell policy
+pub fn initialize_lock_account(
+    ctx: Context<InitializeLockAccount>,
+    sell_policy: SellPolicy,
+) -> Result<()> {
+    sell_policy.validate()?;
 
-    /// Lock token selling if the majority approves
-    pub fn lock_selling(ctx: Context<LockSelling>) -> Result<()> {
-        let governance_account = &mut ctx.accounts.governance_account;
-        let lock_account = &mut ctx.accounts.lock_account;
+    let lock_account = &mut ctx.accounts.lock_account;
+    lock_account.token_mint = ctx.accounts.token_mint.key();
+    lock_account.bump = ctx.bumps.lock_account;
+    lock_account.is_locked = false;
+    lock_account.lock_start_time = 0;
+    lock_account.lock_duration = 0;
+    lock_account.sell_policy = sell_policy;
+    lock_account.current_slot = 0;
+    lock_account.sold_in_slot = 0;
+    lock_account.lock_vote_pending_until = 0;
+    lock_account.circuit_breaker = CircuitBreaker::default();
+    lock_account.paused_until = 0;
+    lock_account.pause_reason = PauseReason::None;
 
-        let total_votes = governance_account.votes_in_favor + governance_account.votes_against;
+    msg!("Lock account initialized for mint {}", lock_account.token_mint);
 
-        // Ensure there are votes before calculating percentage
-        if total_votes == 0 {
-            return Err(GovernanceError::NotEnoughVotesToLock.into());
+    Ok(())
+}
+
+/// Create a proposal for the given governance account; voting opens after `voting_delay`.
+/// The proposer must hold the governance minimum stake
+pub fn create_proposal(
+    ctx: Context<CreateProposal>,
+    kind: ProposalKind,
+    voting_delay: i64,
+    voting_period: i64,
+) -> Result<()> {
+    let voting_rules = ctx.accounts.governance_account.voting_rules;
+    require!(
+        voting_delay >= 0 && voting_delay <= voting_rules.max_voting_delay,
+        GovernanceError::InvalidVotingWindow
+    );
+    require!(
+        voting_period > 0 && voting_period <= voting_rules.max_voting_period,
+        GovernanceError::InvalidVotingWindow
+    );
+
+    let proposer_stake = match &ctx.accounts.staker_account {
+        Some(staker_account) => staker_account.amount_staked,
+        None => 0,
+    };
+    require!(
+        proposer_stake >= voting_rules.min_proposal_stake,
+        GovernanceError::InsufficientProposalStake
+    );
+
+    match kind {
+        ProposalKind::LockSelling { duration_weeks } => {
+            lock_duration_seconds(&ctx.accounts.governance_account, duration_weeks)?;
+        }
+        ProposalKind::ParameterChange {
+            approval_threshold_bps,
+            quorum_threshold_bps,
+            min_lock_time,
+            max_lock_time,
+            voting_rules,
+        } => {
+            validate_thresholds(approval_threshold_bps, quorum_threshold_bps)?;
+            validate_lock_bounds(min_lock_time, max_lock_time)?;
+            voting_rules.validate()?;
         }
+        ProposalKind::SellPolicyChange { sell_policy } => sell_policy.validate()?,
+        ProposalKind::CircuitBreakerChange { circuit_breaker } => circuit_breaker.validate()?,
+        ProposalKind::EmergencyUnlock => {}
+    }
 
-        let approval_percentage =
-            (governance_account.votes_in_favor as f64 / total_votes as f64) * 100.0;
+    let now = Clock::get()?.unix_timestamp;
+    let governance_account = &mut ctx.accounts.governance_account;
+    let proposal = &mut ctx.accounts.proposal;
 
-        require!(
-            approval_percentage >= 60.0,
-            GovernanceError::NotEnoughVotesToLock
-        );
+    // Snapshot the participation required for quorum against the configured supply
+    governance_account.total_supply = ctx.accounts.token_mint.supply;
+    let quorum_supply = match governance_account.quorum_base {
+        QuorumBase::StakedSupply => ctx.accounts.staking_pool.total_staked,
+        QuorumBase::TotalSupply => governance_account.total_supply,
+    };
+    let quorum_votes = required_votes(quorum_supply, governance_account.quorum_threshold_bps)?;
 
-        require!(
-            !lock_account.is_locked,
-            GovernanceError::AlreadyLocked
-        );
+    proposal.governance = governance_account.key();
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.id = governance_account.proposal_count;
+    proposal.kind = kind;
+    proposal.state = if voting_delay == 0 {
+        ProposalState::Active
+    } else {
+        ProposalState::Draft
+    };
+    proposal.created_at = now;
+    proposal.snapshot_slot = Clock::get()?.slot;
+    proposal.voting_starts_at = now
+        .checked_add(voting_delay)
+        .ok_or(GovernanceError::MathOverflow)?;
+    proposal.voting_ends_at = proposal
+        .voting_starts_at
+        .checked_add(voting_period)
+        .ok_or(GovernanceError::MathOverflow)?;
+    proposal.votes_in_favor = 0;
+    proposal.votes_against = 0;
+    proposal.quorum_votes = quorum_votes;
+    proposal.bump = ctx.bumps.proposal;
 
-        lock_account.is_locked = true;
-        lock_account.lock_start_time = Clock::get()?.unix_timestamp;
-        lock_account.lock_duration = 604800; // Minimum lock duration: 1 week
+    governance_account.proposal_count += 1;
 
-        msg!(
-            "Token Selling Locked! Lock Duration: {} seconds",
-            lock_account.lock_duration
-        );
+    // A lock vote that opens immediately escalates the sell tax from now on
+    ctx.accounts.lock_account.open_lock_vote(proposal);
 
-        Ok(())
+    msg!(
+        "Proposal {} created: voting from {} to {}, quorum {} votes",
+        proposal.id,
+        proposal.voting_starts_at,
+        proposal.voting_ends_at,
+        proposal.quorum_votes
+    );
+
+    Ok(())
+}
+
+/// Cast a stake-weighted vote in favor or against a proposal while its voting window is open
+pub fn cast_vote(ctx: Context<CastVote>, in_favor: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let weight = voting_power(
+        &ctx.accounts.governance_account,
+        &ctx.accounts.proposal,
+        &ctx.accounts.staker_account,
+    )?;
+    let proposal = &mut ctx.accounts.proposal;
+    let vote_record = &mut ctx.accounts.vote_record;
+
+    record_vote(
+        proposal,
+        vote_record,
+        ctx.accounts.voter.key(),
+        ctx.bumps.vote_record,
+        in_favor,
+        weight,
+        now,
+    )?;
+    // The first vote activates a draft, which starts its lock vote's escalated tax
+    ctx.accounts.lock_account.open_lock_vote(proposal);
+
+    msg!(
+        "Vote Casted on proposal {}: In Favor: {}, Against: {}",
+        proposal.id,
+        proposal.votes_in_favor,
+        proposal.votes_against
+    );
+
+    Ok(())
+}
+
+/// Switch an existing vote to the other side while the voting window is still open
+pub fn change_vote(ctx: Context<ChangeVote>, in_favor: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    let vote_record = &mut ctx.accounts.vote_record;
+
+    require_voting_open(proposal, now)?;
+    require!(
+        vote_record.in_favor != in_favor,
+        GovernanceError::VoteUnchanged
+    );
+
+    let weight = vote_record.weight;
+    remove_from_tally(proposal, !in_favor, weight)?;
+    add_to_tally(proposal, in_favor, weight)?;
+
+    vote_record.in_favor = in_favor;
+    vote_record.voted_at = now;
+
+    msg!(
+        "Vote Changed on proposal {}: In Favor: {}, Against: {}",
+        proposal.id,
+        proposal.votes_in_favor,
+        proposal.votes_against
+    );
+
+    Ok(())
+}
+
+/// Voting power of a staker on a proposal: the amount they had staked before the
+/// proposal's snapshot slot, if that stake was already old enough when the proposal was created
+pub(crate) fn voting_power(
+    governance_account: &Governance,
+    proposal: &Proposal,
+    staker_account: &StakerAccount,
+) -> Result<u64> {
+    let snapshot = staker_account
+        .checkpoint_before(proposal.snapshot_slot)
+        .ok_or(GovernanceError::NoVotingPower)?;
+    require!(snapshot.amount > 0, GovernanceError::NoVotingPower);
+    require!(
+        proposal.created_at - snapshot.stake_start_time
+            >= governance_account.voting_rules.min_vote_stake_duration,
+        GovernanceError::StakeTooRecent
+    );
+    Ok(snapshot.amount)
+}
+
+/// Record a first-time vote of `weight` on `proposal`, rejecting repeat votes
+pub(crate) fn record_vote(
+    proposal: &mut Account<Proposal>,
+    vote_record: &mut Account<VoteRecord>,
+    voter: Pubkey,
+    bump: u8,
+    in_favor: bool,
+    weight: u64,
+    now: i64,
+) -> Result<()> {
+    require_voting_open(proposal, now)?;
+
+    // A freshly created record still has a default voter
+    require!(
+        vote_record.voter == Pubkey::default(),
+        GovernanceError::AlreadyVoted
+    );
+
+    add_to_tally(proposal, in_favor, weight)?;
+
+    vote_record.proposal = proposal.key();
+    vote_record.voter = voter;
+    vote_record.in_favor = in_favor;
+    vote_record.weight = weight;
+    vote_record.voted_at = now;
+    vote_record.bump = bump;
+
+    Ok(())
+}
+
+/// Add `weight` to one side of a proposal's tally
+fn add_to_tally(proposal: &mut Proposal, in_favor: bool, weight: u64) -> Result<()> {
+    let side = if in_favor {
+        &mut proposal.votes_in_favor
+    } else {
+        &mut proposal.votes_against
+    };
+    *side = side.checked_add(weight).ok_or(GovernanceError::MathOverflow)?;
+    Ok(())
+}
+
+/// Remove `weight` from one side of a proposal's tally
+fn remove_from_tally(proposal: &mut Proposal, in_favor: bool, weight: u64) -> Result<()> {
+    let side = if in_favor {
+        &mut proposal.votes_in_favor
+    } else {
+        &mut proposal.votes_against
+    };
+    *side = side.checked_sub(weight).ok_or(GovernanceError::MathOverflow)?;
+    Ok(())
+}
+
+/// Whether `part / whole` reaches `threshold_bps`, compared as `part * 10_000 >= whole * bps`
+pub(crate) fn meets_threshold(part: u64, whole: u64, threshold_bps: u16) -> Result<bool> {
+    let scaled_part = (part as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(GovernanceError::MathOverflow)?;
+    let scaled_whole = (whole as u128)
+        .checked_mul(threshold_bps as u128)
+        .ok_or(GovernanceError::MathOverflow)?;
+    Ok(scaled_part >= scaled_whole)
+}
+
+/// Smallest vote total that reaches `threshold_bps` of `supply` (rounded up)
+fn required_votes(supply: u64, threshold_bps: u16) -> Result<u64> {
+    let scaled = (supply as u128)
+        .checked_mul(threshold_bps as u128)
+        .ok_or(GovernanceError::MathOverflow)?;
+    let votes = scaled.div_ceil(BPS_DENOMINATOR as u128);
+    u64::try_from(votes).map_err(|_| GovernanceError::MathOverflow.into())
+}
+
+/// Whether enough voting power participated in a proposal to meet its quorum
+pub(crate) fn quorum_reached(proposal: &Proposal) -> Result<bool> {
+    let total_votes = proposal
+        .votes_in_favor
+        .checked_add(proposal.votes_against)
+        .ok_or(GovernanceError::MathOverflow)?;
+    Ok(total_votes > 0 && total_votes >= proposal.quorum_votes)
+}
+
+/// Validate the configurable lock duration bounds (in seconds)
+fn validate_lock_bounds(min_lock_time: i64, max_lock_time: i64) -> Result<()> {
+    require!(
+        min_lock_time > 0 && max_lock_time >= min_lock_time,
+        GovernanceError::InvalidLockTime
+    );
+    Ok(())
+}
+
+/// Convert a proposed lock length in weeks to seconds, enforcing the governance bounds
+fn lock_duration_seconds(governance_account: &Governance, duration_weeks: u8) -> Result<i64> {
+    let lock_duration = i64::from(duration_weeks) * SECONDS_PER_WEEK;
+    require!(
+        lock_duration >= governance_account.min_lock_time,
+        GovernanceError::LockDurationTooShort
+    );
+    require!(
+        lock_duration <= governance_account.max_lock_time,
+        GovernanceError::LockDurationTooLong
+    );
+    Ok(lock_duration)
+}
+
+/// Validate approval and quorum thresholds expressed in basis points
+fn validate_thresholds(approval_threshold_bps: u16, quorum_threshold_bps: u16) -> Result<()> {
+    require!(
+        approval_threshold_bps > 0 && approval_threshold_bps <= BPS_DENOMINATOR,
+        GovernanceError::InvalidThreshold
+    );
+    require!(
+        quorum_threshold_bps > 0 && quorum_threshold_bps <= BPS_DENOMINATOR,
+        GovernanceError::InvalidThreshold
+    );
+    Ok(())
+}
+
+/// Ensure a proposal is accepting votes, activating a draft whose window has opened
+fn require_voting_open(proposal: &mut Proposal, now: i64) -> Result<()> {
+    // Draft proposals become active once their voting window opens
+    if proposal.state == ProposalState::Draft && now >= proposal.voting_starts_at {
+        proposal.state = ProposalState::Active;
     }
 
-    /// Unlock token selling if the majority approves an emergency unlock
-    pub fn emergency_unlock(ctx: Context<EmergencyUnlock>) -> Result<()> {
-        let governance_account = &mut ctx.accounts.governance_account;
-        let lock_account = &mut ctx.accounts.lock_account;
+    require!(
+        proposal.state == ProposalState::Active,
+        GovernanceError::ProposalNotActive
+    );
+    require!(
+        now < proposal.voting_ends_at,
+        GovernanceError::VotingClosed
+    );
+    Ok(())
+}
+
+/// Settle a proposal once voting has ended, or expire a succeeded proposal that was never executed
+pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let governance_account = &ctx.accounts.governance_account;
+    let proposal = &mut ctx.accounts.proposal;
+
+    match proposal.state {
+        ProposalState::Draft | ProposalState::Active => {
+            require!(
+                now >= proposal.voting_ends_at,
+                GovernanceError::VotingStillOpen
+            );
+
+            let total_votes = proposal
+                .votes_in_favor
+                .checked_add(proposal.votes_against)
+                .ok_or(GovernanceError::MathOverflow)?;
+            let has_quorum = quorum_reached(proposal)?;
+            if !has_quorum {
+                msg!(
+                    "Proposal {} did not reach quorum ({} votes required)",
+                    proposal.id,
+                    proposal.quorum_votes
+                );
+            }
 
-        let total_votes = governance_account.votes_in_favor + governance_account.votes_against;
+            let approved = has_quorum
+                && meets_threshold(
+                    proposal.votes_in_favor,
+                    total_votes,
+                    governance_account.approval_threshold_bps,
+                )?;
 
-        // Ensure there are votes before calculating percentage
-        if total_votes == 0 {
-            return Err(GovernanceError::NotEnoughVotesToUnlock.into());
+            proposal.state = if approved {
+                ProposalState::Succeeded
+            } else {
+                ProposalState::Defeated
+            };
         }
+        ProposalState::Succeeded => {
+            require!(
+                now > proposal.voting_ends_at + PROPOSAL_EXECUTION_WINDOW,
+                GovernanceError::ProposalStillExecutable
+            );
+            proposal.state = ProposalState::Expired;
+        }
+        _ => return Err(GovernanceError::ProposalAlreadyFinalized.into()),
+    }
 
-        let approval_percentage =
-            (governance_account.votes_against as f64 / total_votes as f64) * 100.0;
+    // A decided or expired lock vote no longer escalates the sell tax
+    ctx.accounts.lock_account.close_lock_vote(proposal);
 
-        require!(
-            approval_percentage >= 60.0,
-            GovernanceError::NotEnoughVotesToUnlock
-        );
+    msg!("Proposal {} finalized as {:?}", proposal.id, proposal.state);
 
-        lock_account.is_locked = false;
-        lock_account.lock_start_time = 0;
-        lock_account.lock_duration = 0;
+    Ok(())
+}
 
-        msg!("Token Selling Unlocked by Majority Vote!");
+/// Lock token selling by executing a succeeded lock-selling proposal
+pub fn lock_selling(ctx: Context<LockSelling>) -> Result<()> {
+    let governance_account = &ctx.accounts.governance_account;
+    let lock_account = &mut ctx.accounts.lock_account;
+    let proposal = &mut ctx.accounts.proposal;
+    let now = Clock::get()?.unix_timestamp;
 
-        Ok(())
-    }
+    let ProposalKind::LockSelling { duration_weeks } = proposal.kind else {
+        return Err(GovernanceError::WrongProposalKind.into());
+    };
+    require_executable(proposal, now)?;
+
+    // Bounds are re-checked in case governance parameters changed since the proposal was created
+    let lock_duration = lock_duration_seconds(governance_account, duration_weeks)?;
+
+    // An expired lock counts as unlocked even before `expire_lock` clears it
+    require!(
+        !lock_account.is_active(now),
+        GovernanceError::AlreadyLocked
+    );
+
+    lock_account.is_locked = true;
+    lock_account.lock_start_time = now;
+    lock_account.lock_duration = lock_duration;
+
+    proposal.state = ProposalState::Executed;
+
+    msg!(
+        "Token Selling Locked by proposal {}! Lock Duration: {} seconds",
+        proposal.id,
+        lock_account.lock_duration
+    );
+
+    Ok(())
+}
+
+/// Unlock token selling by executing a succeeded emergency-unlock proposal
+pub fn emergency_unlock(ctx: Context<EmergencyUnlock>) -> Result<()> {
+    let lock_account = &mut ctx.accounts.lock_account;
+    let proposal = &mut ctx.accounts.proposal;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        matches!(proposal.kind, ProposalKind::EmergencyUnlock),
+        GovernanceError::WrongProposalKind
+    );
+    require_executable(proposal, now)?;
+    require!(lock_account.is_locked, GovernanceError::NotLocked);
+
+    lock_account.is_locked = false;
+    lock_account.lock_start_time = 0;
+    lock_account.lock_duration = 0;
+
+    proposal.state = ProposalState::Executed;
+
+    msg!("Token Selling Unlocked by proposal {}!", proposal.id);
+
+    Ok(())
+}
+
+/// Clear a lock whose duration has elapsed; callable by anyone
+pub fn expire_lock(ctx: Context<ExpireLock>) -> Result<()> {
+    let lock_account = &mut ctx.accounts.lock_account;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(lock_account.is_locked, GovernanceError::NotLocked);
+    require!(
+        !lock_account.is_active(now),
+        GovernanceError::LockNotExpired
+    );
+
+    emit!(LockExpired {
+        token_mint: lock_account.token_mint,
+        lock_start_time: lock_account.lock_start_time,
+        lock_duration: lock_account.lock_duration,
+        expired_at: now,
+    });
+
+    lock_account.is_locked = false;
+    lock_account.lock_start_time = 0;
+    lock_account.lock_duration = 0;
+
+    msg!("Token Selling Lock expired for mint {}", lock_account.token_mint);
+
+    Ok(())
+}
+
+/// Apply the governance parameters carried by a succeeded parameter-change proposal
+pub fn execute_parameter_change(ctx: Context<ExecuteParameterChange>) -> Result<()> {
+    let governance_account = &mut ctx.accounts.governance_account;
+    let proposal = &mut ctx.accounts.proposal;
+    let now = Clock::get()?.unix_timestamp;
+
+    let ProposalKind::ParameterChange {
+        approval_threshold_bps,
+        quorum_threshold_bps,
+        min_lock_time,
+        max_lock_time,
+        voting_rules,
+    } = proposal.kind
+    else {
+        return Err(GovernanceError::WrongProposalKind.into());
+    };
+    require_executable(proposal, now)?;
+
+    governance_account.approval_threshold_bps = approval_threshold_bps;
+    governance_account.quorum_threshold_bps = quorum_threshold_bps;
+    governance_account.min_lock_time = min_lock_time;
+    governance_account.max_lock_time = max_lock_time;
+    governance_account.voting_rules = voting_rules;
+
+    proposal.state = ProposalState::Executed;
+
+    msg!(
+        "Governance parameters updated by proposal {} (approval: {} bps, quorum: {} bps, lock: {}-{} seconds)",
+        proposal.id,
+        approval_threshold_bps,
+        quorum_threshold_bps,
+        min_lock_time,
+        max_lock_time
+    );
+
+    Ok(())
+}
+
+/// Replace the per-mint sell policy with the one carried by a succeeded proposal
+pub fn execute_sell_policy_change(ctx: Context<ExecuteSellPolicyChange>) -> Result<()> {
+    let lock_account = &mut ctx.accounts.lock_account;
+    let proposal = &mut ctx.accounts.proposal;
+    let now = Clock::get()?.unix_timestamp;
+
+    let ProposalKind::SellPolicyChange { sell_policy } = proposal.kind else {
+        return Err(GovernanceError::WrongProposalKind.into());
+    };
+    require_executable(proposal, now)?;
+
+    lock_account.sell_policy = sell_policy;
+
+    proposal.state = ProposalState::Executed;
+
+    msg!(
+        "Sell policy updated by proposal {} (per wallet: {} per {} seconds, per slot: {}, holdings: {} bps, cooldown: {} seconds, tax: {}/{} bps)",
+        proposal.id,
+        sell_policy.max_sell_per_window,
+        sell_policy.sell_window,
+        sell_policy.max_sell_per_slot,
+        sell_policy.max_sell_bps_of_holdings,
+        sell_policy.sell_cooldown,
+        sell_policy.sell_tax_bps,
+        sell_policy.escalated_sell_tax_bps
+    );
+
+    Ok(())
+}
+
+/// Replace the per-mint price-drop circuit breaker with the one carried by a succeeded proposal
+pub fn execute_circuit_breaker_change(ctx: Context<ExecuteCircuitBreakerChange>) -> Result<()> {
+    let lock_account = &mut ctx.accounts.lock_account;
+    let proposal = &mut ctx.accounts.proposal;
+    let now = Clock::get()?.unix_timestamp;
+
+    let ProposalKind::CircuitBreakerChange { circuit_breaker } = proposal.kind else {
+        return Err(GovernanceError::WrongProposalKind.into());
+    };
+    require_executable(proposal, now)?;
+
+    lock_account.circuit_breaker = circuit_breaker;
+
+    proposal.state = ProposalState::Executed;
+
+    msg!(
+        "Circuit breaker updated by proposal {}: {} bps below the {} second TWAP pauses sells for {} seconds",
+        proposal.id,
+        circuit_breaker.max_drop_bps,
+        circuit_breaker.twap_window,
+        circuit_breaker.pause_duration
+    );
+
+    Ok(())
+}
+
+/// Ensure a proposal reached quorum, succeeded and is still inside its execution window
+fn require_executable(proposal: &Proposal, now: i64) -> Result<()> {
+    require!(quorum_reached(proposal)?, GovernanceError::QuorumNotReached);
+    require!(
+        proposal.state == ProposalState::Succeeded,
+        GovernanceError::ProposalNotSucceeded
+    );
+    require!(
+        now <= proposal.voting_ends_at + PROPOSAL_EXECUTION_WINDOW,
+        GovernanceError::ProposalExpired
+    );
+    Ok(())
+}
+
+/// **Context for Initializing Governance**
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = crate::bonding_curve::is_launch_authority(
+            &token_mint,
+            bonding_curve.as_deref(),
+            authority.key()
+        ) @ GovernanceError::Unauthorized
+    )]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"bonding_curve", token_mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Option<Account<'info, BondingCurve>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Governance::INIT_SPACE,
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump
+    )]
+    pub governance_account: Account<'info, Governance>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// **Context for Initializing the Lock Account**
+#[derive(Accounts)]
+pub struct InitializeLockAccount<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = crate::bonding_curve::is_launch_authority(
+            &token_mint,
+            bonding_curve.as_deref(),
+            authority.key()
+        ) @ GovernanceError::Unauthorized
+    )]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"bonding_curve", token_mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Option<Account<'info, BondingCurve>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + LockAccount::INIT_SPACE,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// **Context for Creating a Proposal**
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance_account.bump
+    )]
+    pub governance_account: Account<'info, Governance>,
+
+    #[account(
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        seeds = [b"staker", staking_pool.key().as_ref(), proposer.key().as_ref()],
+        bump
+    )]
+    pub staker_account: Option<Account<'info, StakerAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [
+            b"proposal",
+            governance_account.key().as_ref(),
+            &governance_account.proposal_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
 }
 
 /// **Context for Casting a Vote**
@@ -97,28 +774,128 @@ pub struct CastVote<'info> {
     #[account(mut)]
     pub voter: Signer<'info>,
 
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance_account.bump
+    )]
+    pub governance_account: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", governance_account.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
     #[account(
         mut,
-        seeds = [b"governance"],
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    #[account(
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        seeds = [b"staker", staking_pool.key().as_ref(), voter.key().as_ref()],
         bump
     )]
+    pub staker_account: Account<'info, StakerAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoteRecord::INIT_SPACE,
+        seeds = [b"vote_record", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// **Context for Changing a Vote**
+#[derive(Accounts)]
+pub struct ChangeVote<'info> {
+    pub voter: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance_account.bump
+    )]
     pub governance_account: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", governance_account.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"vote_record", proposal.key().as_ref(), voter.key().as_ref()],
+        bump = vote_record.bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+}
+
+/// **Context for Finalizing a Proposal**
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance_account.bump
+    )]
+    pub governance_account: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", governance_account.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
 }
 
 /// **Context for Locking Token Selling**
 #[derive(Accounts)]
 pub struct LockSelling<'info> {
+    pub token_mint: Account<'info, Mint>,
+
     #[account(
-        mut,
-        seeds = [b"governance"],
-        bump
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance_account.bump
     )]
     pub governance_account: Account<'info, Governance>,
 
     #[account(
         mut,
-        seeds = [b"lock_account"],
-        bump
+        seeds = [b"proposal", governance_account.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
     )]
     pub lock_account: Account<'info, LockAccount>,
 }
@@ -126,43 +903,532 @@ pub struct LockSelling<'info> {
 /// **Context for Emergency Unlocking**
 #[derive(Accounts)]
 pub struct EmergencyUnlock<'info> {
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance_account.bump
+    )]
+    pub governance_account: Account<'info, Governance>,
+
     #[account(
         mut,
-        seeds = [b"governance"],
-        bump
+        seeds = [b"proposal", governance_account.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+}
+
+/// **Context for Expiring an Elapsed Lock**
+#[derive(Accounts)]
+pub struct ExpireLock<'info> {
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+}
+
+/// **Context for Executing a Parameter Change**
+#[derive(Accounts)]
+pub struct ExecuteParameterChange<'info> {
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance_account.bump
     )]
     pub governance_account: Account<'info, Governance>,
 
     #[account(
         mut,
-        seeds = [b"lock_account"],
-        bump
+        seeds = [b"proposal", governance_account.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// **Context for Executing a Sell Policy Change**
+#[derive(Accounts)]
+pub struct ExecuteSellPolicyChange<'info> {
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance_account.bump
+    )]
+    pub governance_account: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", governance_account.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+}
+
+/// **Context for Executing a Circuit Breaker Change**
+#[derive(Accounts)]
+pub struct ExecuteCircuitBreakerChange<'info> {
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance_account.bump
+    )]
+    pub governance_account: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", governance_account.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
     )]
     pub lock_account: Account<'info, LockAccount>,
 }
 
-/// **Governance Account Struct**
+/// **Governance Account Struct (one per launched token mint)**
 #[account]
+#[derive(InitSpace)]
 pub struct Governance {
+    pub token_mint: Pubkey,        // Mint this governance account controls
+    pub authority: Pubkey,         // Account that initialized governance (mint authority)
+    pub bump: u8,                  // PDA bump for [b"governance", token_mint]
+    pub proposal_count: u64,       // Number of proposals created (next proposal id)
+    pub total_supply: u64,         // Total token supply for governance calculations
+    pub approval_threshold_bps: u16, // Share of votes cast required to approve (6000 = 60%)
+    pub quorum_threshold_bps: u16, // Share of the quorum base that must vote for a decision
+    pub quorum_base: QuorumBase,   // Supply the quorum threshold is measured against
+    pub min_lock_time: i64,        // Minimum lock period (in seconds)
+    pub max_lock_time: i64,        // Maximum lock period (in seconds)
+    pub voting_rules: VotingRules, // Who may propose and vote, and how long votes may run
+}
+
+/// **Proposal and Voting Rules**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct VotingRules {
+    pub min_vote_stake_duration: i64, // Seconds a stake must age before it carries voting power
+    pub min_proposal_stake: u64,   // Tokens a proposer must have staked
+    pub max_voting_delay: i64,     // Longest delay before a proposal's voting opens (in seconds)
+    pub max_voting_period: i64,    // Longest voting period of a proposal (in seconds)
+}
+
+impl VotingRules {
+    /// Reject rules with a negative stake age, no proposer stake or voting bounds outside
+    /// `MAX_VOTING_DURATION`
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_vote_stake_duration >= 0,
+            GovernanceError::InvalidStakeDuration
+        );
+        require!(
+            self.min_proposal_stake > 0,
+            GovernanceError::InvalidProposalStake
+        );
+        require!(
+            self.max_voting_delay >= 0 && self.max_voting_delay <= MAX_VOTING_DURATION,
+            GovernanceError::InvalidVotingBounds
+        );
+        require!(
+            self.max_voting_period > 0 && self.max_voting_period <= MAX_VOTING_DURATION,
+            GovernanceError::InvalidVotingBounds
+        );
+        Ok(())
+    }
+}
+
+/// **Supply a quorum is measured against**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum QuorumBase {
+    StakedSupply,
+    TotalSupply,
+}
+
+/// **Proposal Kind (what a succeeded proposal executes)**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum ProposalKind {
+    LockSelling {
+        duration_weeks: u8,
+    },
+    EmergencyUnlock,
+    ParameterChange {
+        approval_threshold_bps: u16,
+        quorum_threshold_bps: u16,
+        min_lock_time: i64,
+        max_lock_time: i64,
+        voting_rules: VotingRules,
+    },
+    SellPolicyChange {
+        sell_policy: SellPolicy,
+    },
+    CircuitBreakerChange {
+        circuit_breaker: CircuitBreaker,
+    },
+}
+
+/// **Proposal Lifecycle**
+/// Draft → Active → Succeeded/Defeated → Executed/Expired
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum ProposalState {
+    Draft,
+    Active,
+    Succeeded,
+    Defeated,
+    Executed,
+    Expired,
+}
+
+/// **Proposal Account**
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub governance: Pubkey,        // Governance account this proposal belongs to
+    pub proposer: Pubkey,
+    pub id: u64,                   // Sequential id within the governance account
+    pub kind: ProposalKind,
+    pub state: ProposalState,
+    pub created_at: i64,
+    pub snapshot_slot: u64,        // Voting power is the stake held before this slot
+    pub voting_starts_at: i64,
+    pub voting_ends_at: i64,
     pub votes_in_favor: u64,
     pub votes_against: u64,
+    pub quorum_votes: u64,         // Participation required for the proposal to pass
+    pub bump: u8,
+}
+
+/// **Vote Record (one per proposal and voter)**
+#[account]
+#[derive(InitSpace)]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub in_favor: bool,
+    pub weight: u64,               // Vote weight applied to the proposal tally
+    pub voted_at: i64,
+    pub bump: u8,
 }
 
 /// **Lock Account Struct**
 #[account]
+#[derive(InitSpace)]
 pub struct LockAccount {
+    pub token_mint: Pubkey,
+    pub bump: u8,
     pub is_locked: bool,
     pub lock_start_time: i64,
-    pub lock_duration: i64, // Lock duration in seconds (Min: 1 week, Max: 3 months)
+    pub lock_duration: i64, // Lock duration in seconds, within the governance min/max bounds
+    pub sell_policy: SellPolicy,   // Graduated limits applied while selling is unlocked
+    pub current_slot: u64,         // Slot the global sell counter refers to
+    pub sold_in_slot: u64,         // Tokens sold across all wallets in `current_slot`
+    pub lock_vote_pending_until: i64, // End of the latest lock-selling vote
+    pub circuit_breaker: CircuitBreaker, // Price-drop pause configured by governance
+    pub paused_until: i64,         // Sells are paused until this timestamp, without a vote
+    pub pause_reason: PauseReason, // Why the latest automatic pause was triggered
+}
+
+/// **Reason for an automatic sell pause**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum PauseReason {
+    None,
+    PriceDrop,
+}
+
+/// **Graduated Sell Limits (0 disables a limit)**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace)]
+pub struct SellPolicy {
+    pub max_sell_per_window: u64,  // Max tokens one wallet may sell per window
+    pub sell_window: i64,          // Length of the per-wallet window (in seconds)
+    pub max_sell_per_slot: u64,    // Max tokens sold across all wallets per slot
+    pub max_sell_bps_of_holdings: u16, // Max share of a wallet's balance sold at once
+    pub sell_cooldown: i64,        // Seconds a wallet must wait between sells
+    pub sell_tax_bps: u16,         // Share of every sell routed to staking rewards
+    pub escalated_sell_tax_bps: u16, // Sell tax applied while a lock vote is pending
+}
+
+impl SellPolicy {
+    /// Reject policies whose limits cannot be enforced
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.max_sell_per_window == 0 || self.sell_window > 0,
+            GovernanceError::InvalidSellPolicy
+        );
+        require!(
+            self.sell_window >= 0 && self.sell_window <= MAX_SELL_PERIOD,
+            GovernanceError::InvalidSellPolicy
+        );
+        require!(
+            self.max_sell_bps_of_holdings <= BPS_DENOMINATOR,
+            GovernanceError::InvalidSellPolicy
+        );
+        require!(
+            self.sell_cooldown >= 0 && self.sell_cooldown <= MAX_SELL_PERIOD,
+            GovernanceError::InvalidSellPolicy
+        );
+        require!(
+            self.sell_tax_bps <= BPS_DENOMINATOR
+                && self.escalated_sell_tax_bps <= BPS_DENOMINATOR,
+            GovernanceError::InvalidSellPolicy
+        );
+        Ok(())
+    }
 }
 
-/// **Governance Errors**
-#[error_code]
+impl LockAccount {
+    /// Unix timestamp at which the current lock ends
+    pub fn lock_end_time(&self) -> i64 {
+        self.lock_start_time + self.lock_duration
+    }
+
+    /// Whether selling is locked at `now`; an elapsed lock is treated as unlocked
+    pub fn is_active(&self, now: i64) -> bool {
+        self.is_locked && now < self.lock_end_time()
+    }
+
+    /// Whether an automatic pause is holding sells at `now`
+    pub fn is_paused(&self, now: i64) -> bool {
+        now < self.paused_until
+    }
+
+    /// Escalate the sell tax until the end of `proposal`'s vote, once it is an active
+    /// lock-selling vote
+    pub fn open_lock_vote(&mut self, proposal: &Proposal) {
+        if matches!(proposal.kind, ProposalKind::LockSelling { .. })
+            && proposal.state == ProposalState::Active
+        {
+            self.lock_vote_pending_until = self.lock_vote_pending_until.max(proposal.voting_ends_at);
+        }
+    }
+
+    /// Drop the escalation held for a decided lock-selling `proposal`, unless a later lock vote
+    /// still holds it
+    pub fn close_lock_vote(&mut self, proposal: &Proposal) {
+        if matches!(proposal.kind, ProposalKind::LockSelling { .. })
+            && self.lock_vote_pending_until <= proposal.voting_ends_at
+        {
+            self.lock_vote_pending_until = 0;
+        }
+    }
+
+    /// Sell tax in basis points at `now`, escalated while a lock vote is pending
+    pub fn sell_tax_bps(&self, now: i64) -> u16 {
+        let policy = &self.sell_policy;
+        if now < self.lock_vote_pending_until {
+            policy.sell_tax_bps.max(policy.escalated_sell_tax_bps)
+        } else {
+            policy.sell_tax_bps
+        }
+    }
+}
+
+/// **Emitted when an elapsed lock is cleared by `expire_lock`**
+#[event]
+pub struct LockExpired {
+    pub token_mint: Pubkey,
+    pub lock_start_time: i64,
+    pub lock_duration: i64,
+    pub expired_at: i64,
+}
+
+/// **Governance Errors (codes 6000-6099)**
+#[error_code(offset = 6000)]
 pub enum GovernanceError {
-    #[msg("Not enough votes to lock selling.")]
-    NotEnoughVotesToLock,
-    #[msg("Not enough votes to unlock selling.")]
-    NotEnoughVotesToUnlock,
     #[msg("Selling is already locked.")]
     AlreadyLocked,
+    #[msg("Only the mint authority can initialize governance accounts.")]
+    Unauthorized,
+    #[msg("Thresholds must be expressed in basis points between 1 and 10000.")]
+    InvalidThreshold,
+    #[msg("Lock bounds must be positive with the maximum at least the minimum.")]
+    InvalidLockTime,
+    #[msg("Voting delay and voting period must be within the governance bounds.")]
+    InvalidVotingWindow,
+    #[msg("Proposal is not open for voting.")]
+    ProposalNotActive,
+    #[msg("Voting on this proposal has closed.")]
+    VotingClosed,
+    #[msg("Voting on this proposal is still open.")]
+    VotingStillOpen,
+    #[msg("Proposal has already been finalized.")]
+    ProposalAlreadyFinalized,
+    #[msg("Proposal can still be executed.")]
+    ProposalStillExecutable,
+    #[msg("Proposal has not succeeded.")]
+    ProposalNotSucceeded,
+    #[msg("Proposal execution window has passed.")]
+    ProposalExpired,
+    #[msg("Proposal kind does not match this instruction.")]
+    WrongProposalKind,
+    #[msg("Voter has already voted on this proposal.")]
+    AlreadyVoted,
+    #[msg("Vote already has the requested direction.")]
+    VoteUnchanged,
+    #[msg("Minimum vote stake duration cannot be negative.")]
+    InvalidStakeDuration,
+    #[msg("Voter had no staked tokens at the proposal snapshot and therefore no voting power.")]
+    NoVotingPower,
+    #[msg("Stake is too recent to carry voting power.")]
+    StakeTooRecent,
+    #[msg("Arithmetic overflow in governance calculation.")]
+    MathOverflow,
+    #[msg("Not enough voting power participated to reach quorum.")]
+    QuorumNotReached,
+    #[msg("Lock duration is shorter than the governance minimum.")]
+    LockDurationTooShort,
+    #[msg("Lock duration is longer than the governance maximum.")]
+    LockDurationTooLong,
+    #[msg("Selling is not locked.")]
+    NotLocked,
+    #[msg("Lock period has not elapsed yet.")]
+    LockNotExpired,
+    #[msg("Sell policy needs a positive window for wallet limits, caps and taxes of at most 10000 bps, and a window and cooldown of at most 30 days.")]
+    InvalidSellPolicy,
+    #[msg("Voting delay bound cannot be negative, voting period bound must be positive, and both are capped at 30 days.")]
+    InvalidVotingBounds,
+    #[msg("Proposer has not staked enough tokens to create a proposal.")]
+    InsufficientProposalStake,
+    #[msg("Minimum proposal stake must be positive.")]
+    InvalidProposalStake,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meets_threshold_is_inclusive() {
+        assert!(meets_threshold(60, 100, 6_000).unwrap());
+        assert!(!meets_threshold(59, 100, 6_000).unwrap());
+        assert!(meets_threshold(1, 1, BPS_DENOMINATOR).unwrap());
+        assert!(meets_threshold(u64::MAX, u64::MAX, 6_000).unwrap());
+    }
+
+    #[test]
+    fn required_votes_rounds_up() {
+        assert_eq!(required_votes(100, 6_000).unwrap(), 60);
+        assert_eq!(required_votes(101, 6_000).unwrap(), 61);
+        assert_eq!(required_votes(1, 1).unwrap(), 1);
+        assert_eq!(required_votes(0, 6_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn required_votes_is_the_smallest_passing_total() {
+        for supply in [1, 3, 7, 999, 1_000_001, u64::MAX / 3] {
+            for threshold_bps in [1, 3_333, 5_000, 6_667, BPS_DENOMINATOR] {
+                let votes = required_votes(supply, threshold_bps).unwrap();
+                assert!(meets_threshold(votes, supply, threshold_bps).unwrap());
+                assert!(!meets_threshold(votes - 1, supply, threshold_bps).unwrap());
+            }
+        }
+    }
+
+    fn lock_vote(state: ProposalState, voting_ends_at: i64) -> Proposal {
+        Proposal {
+            governance: Pubkey::default(),
+            proposer: Pubkey::default(),
+            id: 0,
+            kind: ProposalKind::LockSelling { duration_weeks: 1 },
+            state,
+            created_at: 0,
+            snapshot_slot: 0,
+            voting_starts_at: 0,
+            voting_ends_at,
+            votes_in_favor: 0,
+            votes_against: 0,
+            quorum_votes: 0,
+            bump: 0,
+        }
+    }
+
+    fn lock_account() -> LockAccount {
+        LockAccount {
+            token_mint: Pubkey::default(),
+            bump: 0,
+            is_locked: false,
+            lock_start_time: 0,
+            lock_duration: 0,
+            sell_policy: SellPolicy {
+                sell_tax_bps: 100,
+                escalated_sell_tax_bps: 500,
+                ..SellPolicy::default()
+            },
+            current_slot: 0,
+            sold_in_slot: 0,
+            lock_vote_pending_until: 0,
+            circuit_breaker: CircuitBreaker::default(),
+            paused_until: 0,
+            pause_reason: PauseReason::None,
+        }
+    }
+
+    #[test]
+    fn lock_votes_escalate_the_tax_only_while_active() {
+        let mut lock_account = lock_account();
+
+        // A draft does not escalate the tax before its voting opens
+        lock_account.open_lock_vote(&lock_vote(ProposalState::Draft, 1_000));
+        assert_eq!(lock_account.sell_tax_bps(0), 100);
+
+        lock_account.open_lock_vote(&lock_vote(ProposalState::Active, 1_000));
+        assert_eq!(lock_account.sell_tax_bps(999), 500);
+        assert_eq!(lock_account.sell_tax_bps(1_000), 100);
+
+        // Deciding the vote ends the escalation early
+        lock_account.close_lock_vote(&lock_vote(ProposalState::Succeeded, 1_000));
+        assert_eq!(lock_account.sell_tax_bps(500), 100);
+    }
+
+    #[test]
+    fn closing_a_lock_vote_keeps_a_later_one_escalated() {
+        let mut lock_account = lock_account();
+        lock_account.open_lock_vote(&lock_vote(ProposalState::Active, 1_000));
+        lock_account.open_lock_vote(&lock_vote(ProposalState::Active, 2_000));
+
+        lock_account.close_lock_vote(&lock_vote(ProposalState::Defeated, 1_000));
+        assert_eq!(lock_account.lock_vote_pending_until, 2_000);
+
+        lock_account.close_lock_vote(&lock_vote(ProposalState::Expired, 2_000));
+        assert_eq!(lock_account.lock_vote_pending_until, 0);
+    }
+
+    #[test]
+    fn voting_rules_require_a_proposal_stake() {
+        let mut voting_rules = VotingRules {
+            min_vote_stake_duration: 0,
+            min_proposal_stake: 1,
+            max_voting_delay: 86_400,
+            max_voting_period: SECONDS_PER_WEEK,
+        };
+        assert!(voting_rules.validate().is_ok());
+
+        voting_rules.min_proposal_stake = 0;
+        assert_eq!(
+            voting_rules.validate().unwrap_err(),
+            GovernanceError::InvalidProposalStake.into()
+        );
+    }
 }
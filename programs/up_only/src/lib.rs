@@ -1,127 +1,336 @@
+// Instruction handlers share their names with the `#[program]` entrypoints below.
+#![allow(ambiguous_glob_reexports)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
 
 pub mod token_creation;
+pub mod bonding_curve;
 pub mod staking;
 pub mod governance;
 pub mod trading;
+pub mod fees;
+pub mod otc;
 pub mod lock_selling;
 
-pub use crate::token_creation::create_token;
-pub use crate::staking::stake;
-pub use crate::governance::cast_vote;
-pub use crate::trading::{buy_token, sell_token};
-pub use crate::lock_selling::{vote_to_lock, emergency_unlock};
+pub use crate::token_creation::*;
+pub use crate::bonding_curve::*;
+pub use crate::staking::*;
+pub use crate::governance::*;
+pub use crate::trading::*;
+pub use crate::fees::*;
+pub use crate::otc::*;
+pub use crate::lock_selling::*;
 
-declare_id!("YourProgramPublicKeyHere");
+declare_id!("71SrXrVtxh3efZgxQRidBJeVZY1PKaTY6mcRRomVcuy8");
 
 #[program]
 pub mod up_only {
     use super::*;
 
+    // ---------------------------------------------------------------
+    // Token creation
+    // ---------------------------------------------------------------
+
     pub fn create_token(
         ctx: Context<CreateToken>,
         name: String,
         symbol: String,
         decimals: u8,
     ) -> Result<()> {
-        token_creation::create_token(ctx, name, symbol, decimals) // ✅ Correct function call
+        token_creation::create_token(ctx, name, symbol, decimals)
+    }
+
+    pub fn mint_tokens(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
+        token_creation::mint_tokens(ctx, amount)
+    }
+
+    // ---------------------------------------------------------------
+    // Bonding curve
+    // ---------------------------------------------------------------
+
+    pub fn create_curve_token(
+        ctx: Context<CreateCurveToken>,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        curve: CurveKind,
+        graduation_target: GraduationTarget,
+    ) -> Result<()> {
+        bonding_curve::create_curve_token(ctx, name, symbol, decimals, curve, graduation_target)
+    }
+
+    pub fn buy_from_curve(
+        ctx: Context<BuyFromCurve>,
+        amount: u64,
+        max_amount_in: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        bonding_curve::buy_from_curve(ctx, amount, max_amount_in, deadline)
+    }
+
+    pub fn sell_to_curve(
+        ctx: Context<SellToCurve>,
+        amount: u64,
+        min_amount_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        bonding_curve::sell_to_curve(ctx, amount, min_amount_out, deadline)
+    }
+
+    pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
+        bonding_curve::graduate(ctx)
+    }
+
+    // ---------------------------------------------------------------
+    // Staking
+    // ---------------------------------------------------------------
+
+    pub fn initialize_staking_pool(
+        ctx: Context<InitializeStakingPool>,
+        min_stake_duration: i64,
+    ) -> Result<()> {
+        staking::initialize_staking_pool(ctx, min_stake_duration)
     }
 
     pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
-        staking::stake(ctx, amount) // ✅ Correct function call
+        staking::stake(ctx, amount)
+    }
+
+    pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
+        staking::unstake(ctx)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        staking::claim_rewards(ctx)
     }
 
-    pub fn cast_vote(ctx: Context<governance::CastVote>, in_favor: bool) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Governance
+    // ---------------------------------------------------------------
+
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        approval_threshold_bps: u16,
+        quorum_threshold_bps: u16,
+        quorum_base: QuorumBase,
+        min_lock_time: i64,
+        max_lock_time: i64,
+        voting_rules: VotingRules,
+    ) -> Result<()> {
+        governance::initialize_governance(
+            ctx,
+            approval_threshold_bps,
+            quorum_threshold_bps,
+            quorum_base,
+            min_lock_time,
+            max_lock_time,
+            voting_rules,
+        )
+    }
+
+    pub fn initialize_lock_account(
+        ctx: Context<InitializeLockAccount>,
+        sell_policy: SellPolicy,
+    ) -> Result<()> {
+        governance::initialize_lock_account(ctx, sell_policy)
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        kind: ProposalKind,
+        voting_delay: i64,
+        voting_period: i64,
+    ) -> Result<()> {
+        governance::create_proposal(ctx, kind, voting_delay, voting_period)
+    }
+
+    pub fn cast_vote(ctx: Context<CastVote>, in_favor: bool) -> Result<()> {
         governance::cast_vote(ctx, in_favor)
-    }    
+    }
 
-    pub fn buy_token(ctx: Context<BuyToken>, amount: u64) -> Result<()> {
-        trading::buy_token(ctx, amount) // ✅ Correct function call
+    pub fn change_vote(ctx: Context<ChangeVote>, in_favor: bool) -> Result<()> {
+        governance::change_vote(ctx, in_favor)
     }
 
-    pub fn sell_token(ctx: Context<SellToken>, amount: u64) -> Result<()> {
-        trading::sell_token(ctx, amount) // ✅ Correct function call
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+        governance::finalize_proposal(ctx)
     }
 
-    pub fn vote_to_lock(ctx: Context<VoteToLock>, token_mint: Pubkey, duration_weeks: u8) -> Result<()> {
-        lock_selling::vote_to_lock(ctx, token_mint, duration_weeks) // ✅ Correct function call
+    pub fn lock_selling(ctx: Context<LockSelling>) -> Result<()> {
+        governance::lock_selling(ctx)
     }
 
-    pub fn emergency_unlock(ctx: Context<EmergencyUnlock>, token_mint: Pubkey) -> Result<()> {
-        lock_selling::emergency_unlock(ctx, token_mint) // ✅ Correct function call
+    pub fn emergency_unlock(ctx: Context<EmergencyUnlock>) -> Result<()> {
+        governance::emergency_unlock(ctx)
     }
-}
 
-#[derive(Accounts)]
-pub struct CreateToken<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
+    pub fn expire_lock(ctx: Context<ExpireLock>) -> Result<()> {
+        governance::expire_lock(ctx)
+    }
 
-    #[account(init_if_needed, payer = payer, space = 8 + 128)]
-    pub token_account: Account<'info, TokenAccount>,
+    pub fn execute_parameter_change(ctx: Context<ExecuteParameterChange>) -> Result<()> {
+        governance::execute_parameter_change(ctx)
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    pub fn execute_sell_policy_change(ctx: Context<ExecuteSellPolicyChange>) -> Result<()> {
+        governance::execute_sell_policy_change(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct Stake<'info> {
-    #[account(mut)]
-    pub staker: Signer<'info>,
-    #[account(mut)]
-    pub staking_pool: Account<'info, StakingPool>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn execute_circuit_breaker_change(ctx: Context<ExecuteCircuitBreakerChange>) -> Result<()> {
+        governance::execute_circuit_breaker_change(ctx)
+    }
 
-#[account]
-pub struct StakingPool {
-    pub total_staked: u64,
-    pub staker_count: u32, // ✅ Tracks total stakers
-    pub bump: u8,          // ✅ Required for PDAs
-}
+    // ---------------------------------------------------------------
+    // Lock selling
+    // ---------------------------------------------------------------
 
-#[derive(Accounts)]
-pub struct CastVote<'info> {
-    #[account(mut)]
-    pub voter: Signer<'info>,
-    #[account(mut)]
-    pub governance_account: Account<'info, Governance>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn vote_lock_selling(ctx: Context<VoteLockSelling>, vote: bool) -> Result<()> {
+        lock_selling::vote_lock_selling(ctx, vote)
+    }
 
-#[account]
-pub struct Governance {
-    pub votes: i64,
-}
+    // ---------------------------------------------------------------
+    // Trading
+    // ---------------------------------------------------------------
 
-#[derive(Accounts)]
-pub struct BuyToken<'info> {
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    #[account(mut)]
-    pub token_account: Account<'info, TokenAccount>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn initialize_trading_pool(
+        ctx: Context<InitializeTradingPool>,
+        creator_fee_bps: u16,
+    ) -> Result<()> {
+        trading::initialize_trading_pool(ctx, creator_fee_bps)
+    }
 
-#[derive(Accounts)]
-pub struct SellToken<'info> {
-    #[account(mut)]
-    pub seller: Signer<'info>,
-    #[account(mut)]
-    pub token_account: Account<'info, TokenAccount>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn initialize_pool(ctx: Context<InitializePool>, lp_fee_bps: u16) -> Result<()> {
+        trading::initialize_pool(ctx, lp_fee_bps)
+    }
 
-#[derive(Accounts)]
-pub struct VoteToLock<'info> {
-    #[account(mut)]
-    pub governance_account: Account<'info, Governance>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn add_liquidity(
+        ctx: Context<AddLiquidity>,
+        max_token_amount: u64,
+        max_quote_amount: u64,
+    ) -> Result<()> {
+        trading::add_liquidity(ctx, max_token_amount, max_quote_amount)
+    }
+
+    pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, shares: u64) -> Result<()> {
+        trading::remove_liquidity(ctx, shares)
+    }
+
+    pub fn swap_quote_for_tokens(
+        ctx: Context<SwapQuoteForTokens>,
+        quote_amount: u64,
+        min_amount_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        trading::swap_quote_for_tokens(ctx, quote_amount, min_amount_out, deadline)
+    }
+
+    pub fn swap_quote_for_exact_tokens(
+        ctx: Context<SwapQuoteForTokens>,
+        token_amount: u64,
+        max_amount_in: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        trading::swap_quote_for_exact_tokens(ctx, token_amount, max_amount_in, deadline)
+    }
+
+    pub fn sell_tokens(
+        ctx: Context<SellTokens>,
+        amount: u64,
+        min_amount_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        trading::sell_tokens(ctx, amount, min_amount_out, deadline)
+    }
+
+    pub fn sell_tokens_for_exact_quote(
+        ctx: Context<SellTokens>,
+        quote_amount: u64,
+        max_amount_in: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        trading::sell_tokens_for_exact_quote(ctx, quote_amount, max_amount_in, deadline)
+    }
+
+    pub fn get_twap(ctx: Context<GetTwap>, period: i64) -> Result<u128> {
+        trading::get_twap(ctx, period)
+    }
+
+    // ---------------------------------------------------------------
+    // Order book
+    // ---------------------------------------------------------------
+
+    pub fn initialize_order_book(ctx: Context<InitializeOrderBook>) -> Result<()> {
+        trading::initialize_order_book(ctx)
+    }
+
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        side: OrderSide,
+        price: u64,
+        amount: u64,
+    ) -> Result<()> {
+        trading::place_order(ctx, side, price, amount)
+    }
+
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        trading::cancel_order(ctx)
+    }
+
+    pub fn match_orders(ctx: Context<MatchOrders>) -> Result<()> {
+        trading::match_orders(ctx)
+    }
+
+    // ---------------------------------------------------------------
+    // OTC offers
+    // ---------------------------------------------------------------
+
+    pub fn create_offer(
+        ctx: Context<CreateOffer>,
+        offer_id: u64,
+        amount: u64,
+        price_per_token: u64,
+        quote_mint: Option<Pubkey>,
+        expires_at: i64,
+    ) -> Result<()> {
+        otc::create_offer(ctx, offer_id, amount, price_per_token, quote_mint, expires_at)
+    }
+
+    pub fn fill_offer(ctx: Context<FillOffer>, amount: u64) -> Result<()> {
+        otc::fill_offer(ctx, amount)
+    }
+
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        otc::cancel_offer(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct EmergencyUnlock<'info> {
-    #[account(mut)]
-    pub governance_account: Account<'info, Governance>,
-    pub system_program: Program<'info, System>,
+    // ---------------------------------------------------------------
+    // Fees
+    // ---------------------------------------------------------------
+
+    pub fn initialize_protocol_config(
+        ctx: Context<InitializeProtocolConfig>,
+        protocol_fee_bps: u16,
+    ) -> Result<()> {
+        fees::initialize_protocol_config(ctx, protocol_fee_bps)
+    }
+
+    pub fn set_protocol_fee(ctx: Context<SetProtocolFee>, protocol_fee_bps: u16) -> Result<()> {
+        fees::set_protocol_fee(ctx, protocol_fee_bps)
+    }
+
+    pub fn set_creator_fee(ctx: Context<SetCreatorFee>, creator_fee_bps: u16) -> Result<()> {
+        fees::set_creator_fee(ctx, creator_fee_bps)
+    }
+
+    pub fn set_lp_fee(ctx: Context<SetLpFee>, lp_fee_bps: u16) -> Result<()> {
+        fees::set_lp_fee(ctx, lp_fee_bps)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        fees::claim_creator_fees(ctx)
+    }
+
+    pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+        fees::withdraw_protocol_fees(ctx, amount)
+    }
 }
@@ -1,50 +1,51 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
 
-#[program]
-pub mod lock_selling {
-    use super::*;
-
-    /// Casts a vote to lock or unlock token selling
-    pub fn vote_lock_selling(ctx: Context<VoteLockSelling>, vote: bool) -> Result<()> {
-        let governance = &mut ctx.accounts.governance;
-        let voter_account = &ctx.accounts.voter_account;
-
-        // Ensure the voter has governance power
-        require!(
-            voter_account.holdings > 0,
-            LockSellingError::NoVotingPower
-        );
-
-        // Apply vote weight based on governance power
-        if vote {
-            governance.lock_votes += voter_account.holdings;
-        } else {
-            governance.unlock_votes += voter_account.holdings;
-        }
-
-        // **Check if selling should be locked**
-        let lock_threshold = (governance.total_supply as f64 * governance.majority_threshold) as u64;
-        if governance.lock_votes >= lock_threshold {
-            governance.selling_locked = true;
-            governance.lock_end_timestamp = Clock::get()?.unix_timestamp + governance.min_lock_time;
-            msg!("Selling has been locked by majority vote.");
-        }
-
-        // **Check if selling should be unlocked**
-        let unlock_threshold = (governance.total_supply as f64 * governance.majority_threshold) as u64;
-        if governance.unlock_votes >= unlock_threshold {
-            require!(
-                Clock::get()?.unix_timestamp >= governance.lock_end_timestamp,
-                LockSellingError::CannotUnlockYet
-            );
-            governance.selling_locked = false;
-            governance.lock_votes = 0;
-            governance.unlock_votes = 0;
-            msg!("Selling has been unlocked by majority vote.");
-        }
-
-        Ok(())
+use crate::governance::{
+    self, Governance, LockAccount, Proposal, ProposalKind, ProposalState, VoteRecord,
+};
+use crate::staking::{StakerAccount, StakingPool};
+
+/// Casts a stake-weighted vote on a lock or unlock proposal
+pub fn vote_lock_selling(ctx: Context<VoteLockSelling>, vote: bool) -> Result<()> {
+    let governance = &ctx.accounts.governance;
+    let proposal = &mut ctx.accounts.proposal;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        matches!(
+            proposal.kind,
+            ProposalKind::LockSelling { .. } | ProposalKind::EmergencyUnlock
+        ),
+        LockSellingError::NotALockProposal
+    );
+
+    // Apply vote weight based on the voter's stake
+    let weight = governance::voting_power(governance, proposal, &ctx.accounts.staker_account)?;
+    governance::record_vote(
+        proposal,
+        &mut ctx.accounts.vote_record,
+        ctx.accounts.voter.key(),
+        ctx.bumps.vote_record,
+        vote,
+        weight,
+        now,
+    )?;
+    ctx.accounts.lock_account.open_lock_vote(proposal);
+
+    // **Approval by a majority of the total supply decides the proposal without waiting for voting to end**
+    let supply_majority = governance::meets_threshold(
+        proposal.votes_in_favor,
+        governance.total_supply,
+        governance.approval_threshold_bps,
+    )?;
+    if supply_majority && governance::quorum_reached(proposal)? {
+        proposal.state = ProposalState::Succeeded;
+        ctx.accounts.lock_account.close_lock_vote(proposal);
+        msg!("Proposal {} passed by supply majority.", proposal.id);
     }
+
+    Ok(())
 }
 
 /// **Context for Voting on Selling Lock**
@@ -53,40 +54,55 @@ pub struct VoteLockSelling<'info> {
     #[account(mut)]
     pub voter: Signer<'info>,
 
-    #[account(mut)]
-    pub voter_account: Account<'info, Voter>,
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"governance", token_mint.key().as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", governance.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
 
     #[account(
         mut,
-        seeds = [b"governance"],
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    #[account(
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        seeds = [b"staker", staking_pool.key().as_ref(), voter.key().as_ref()],
         bump
     )]
-    pub governance: Account<'info, Governance>,
-}
+    pub staker_account: Account<'info, StakerAccount>,
 
-/// **Governance Account (Manages Voting and Locks)**
-#[account]
-pub struct Governance {
-    pub selling_locked: bool,      // Whether selling is currently locked
-    pub lock_votes: u64,           // Total votes in favor of locking
-    pub unlock_votes: u64,         // Total votes in favor of unlocking
-    pub total_supply: u64,         // Total token supply for governance calculations
-    pub majority_threshold: f64,   // % of votes required to make a decision (e.g., 0.6 for 60%)
-    pub lock_end_timestamp: i64,   // Unix timestamp when selling can be unlocked
-    pub min_lock_time: i64,        // Minimum lock period (in seconds)
-}
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoteRecord::INIT_SPACE,
+        seeds = [b"vote_record", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
 
-/// **Voter Account (Tracks Governance Power)**
-#[account]
-pub struct Voter {
-    pub holdings: u64, // Number of tokens held (governance power)
+    pub system_program: Program<'info, System>,
 }
 
-/// **Errors for Lock Selling Mechanism**
-#[error_code]
+/// **Errors for Lock Selling Mechanism (codes 6600-6699)**
+#[error_code(offset = 6600)]
 pub enum LockSellingError {
-    #[msg("User has no governance power to vote.")]
-    NoVotingPower,
-    #[msg("Cannot unlock selling yet; minimum lock period has not passed.")]
-    CannotUnlockYet,
+    #[msg("Proposal is not a lock or unlock proposal.")]
+    NotALockProposal,
 }
@@ -0,0 +1,477 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+
+use crate::bonding_curve::token_unit;
+use crate::governance::{LockAccount, BPS_DENOMINATOR};
+use crate::staking::StakingPool;
+use crate::trading::{enforce_sell_policy, sell_tax, SellerState, TradingPool};
+
+/// Post an OTC offer, moving `amount` tokens into an escrow owned by the offer. Posting counts
+/// as the sell: it must pass the sell policy, and the sell tax rate at posting is charged on
+/// each fill, so a cancelled or expired offer returns everything it still holds
+pub fn create_offer(
+    ctx: Context<CreateOffer>,
+    offer_id: u64,
+    amount: u64,
+    price_per_token: u64,
+    quote_mint: Option<Pubkey>,
+    expires_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !ctx.accounts.lock_account.is_active(now),
+        OtcError::SellingLocked
+    );
+    require!(
+        !ctx.accounts.lock_account.is_paused(now),
+        OtcError::SellingPaused
+    );
+    require!(amount > 0 && price_per_token > 0, OtcError::InvalidOffer);
+    require!(expires_at > now, OtcError::InvalidOffer);
+
+    let tax = sell_tax(&ctx.accounts.lock_account, amount, now)?;
+    require!(amount > tax, OtcError::InvalidOffer);
+
+    let seller_state = &mut ctx.accounts.seller_state;
+    if seller_state.seller == Pubkey::default() {
+        seller_state.token_mint = ctx.accounts.token_mint.key();
+        seller_state.seller = ctx.accounts.seller.key();
+        seller_state.bump = ctx.bumps.seller_state;
+    }
+    enforce_sell_policy(
+        &mut ctx.accounts.lock_account,
+        seller_state,
+        ctx.accounts.seller_token_account.amount,
+        amount,
+        now,
+        Clock::get()?.slot,
+    )?;
+
+    token::transfer(ctx.accounts.transfer_context(), amount)?;
+
+    let offer = &mut ctx.accounts.offer;
+    offer.seller = ctx.accounts.seller.key();
+    offer.token_mint = ctx.accounts.token_mint.key();
+    offer.offer_id = offer_id;
+    offer.quote_mint = quote_mint;
+    offer.price_per_token = price_per_token;
+    offer.amount_remaining = amount;
+    offer.sell_tax_bps = ctx.accounts.lock_account.sell_tax_bps(now);
+    offer.expires_at = expires_at;
+    offer.bump = ctx.bumps.offer;
+    offer.escrow_bump = ctx.bumps.escrow;
+
+    msg!(
+        "User {} offered {} tokens at {} per token until {}",
+        offer.seller,
+        amount,
+        price_per_token,
+        expires_at
+    );
+
+    Ok(())
+}
+
+/// Take `amount` tokens from an open offer, paying the seller directly for what is left after the
+/// sell tax; a full fill closes the offer
+pub fn fill_offer(ctx: Context<FillOffer>, amount: u64) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !ctx.accounts.lock_account.is_active(now),
+        OtcError::SellingLocked
+    );
+    require!(
+        !ctx.accounts.lock_account.is_paused(now),
+        OtcError::SellingPaused
+    );
+    require!(now < offer.expires_at, OtcError::OfferExpired);
+    require!(
+        amount > 0 && amount <= offer.amount_remaining,
+        OtcError::InvalidFillAmount
+    );
+
+    let tax = offer.fill_tax(amount)?;
+    let received = amount - tax;
+    require!(received > 0, OtcError::InvalidFillAmount);
+
+    let unit = token_unit(ctx.accounts.token_mint.decimals)?;
+    let payment = u64::try_from((received as u128 * offer.price_per_token as u128).div_ceil(unit))
+        .map_err(|_| OtcError::MathOverflow)?;
+
+    // Pay the seller in SOL or in the offer's quote mint
+    match offer.quote_mint {
+        None => system_program::transfer(ctx.accounts.sol_payment_context(), payment)?,
+        Some(_) => token::transfer(ctx.accounts.quote_payment_context()?, payment)?,
+    }
+
+    let seller_key = offer.seller;
+    let offer_id = offer.offer_id.to_le_bytes();
+    let seeds = &[b"offer", seller_key.as_ref(), offer_id.as_ref(), &[offer.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        ctx.accounts.release_context().with_signer(signer_seeds),
+        received,
+    )?;
+    if tax > 0 {
+        token::transfer(
+            ctx.accounts.tax_transfer_context().with_signer(signer_seeds),
+            tax,
+        )?;
+        ctx.accounts.staking_pool.distribute_rewards(tax)?;
+    }
+
+    let trading_pool = &mut ctx.accounts.trading_pool;
+    trading_pool.total_traded = trading_pool
+        .total_traded
+        .checked_add(amount)
+        .ok_or(OtcError::MathOverflow)?;
+
+    let offer = &mut ctx.accounts.offer;
+    offer.amount_remaining -= amount;
+
+    msg!(
+        "User {} bought {} tokens from {} for {}",
+        ctx.accounts.buyer.key(),
+        received,
+        seller_key,
+        payment
+    );
+
+    if offer.amount_remaining == 0 {
+        token::close_account(ctx.accounts.close_escrow_context().with_signer(signer_seeds))?;
+        ctx.accounts
+            .offer
+            .close(ctx.accounts.seller.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+/// Withdraw an open offer, returning the unfilled tokens to the seller
+pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    require!(
+        ctx.accounts.caller.key() == offer.seller
+            || Clock::get()?.unix_timestamp >= offer.expires_at,
+        OtcError::Unauthorized
+    );
+
+    let seller_key = offer.seller;
+    let offer_id = offer.offer_id.to_le_bytes();
+    let seeds = &[b"offer", seller_key.as_ref(), offer_id.as_ref(), &[offer.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let amount = offer.amount_remaining;
+    token::transfer(
+        ctx.accounts.refund_context().with_signer(signer_seeds),
+        amount,
+    )?;
+    token::close_account(ctx.accounts.close_escrow_context().with_signer(signer_seeds))?;
+
+    msg!(
+        "Offer {} by {} closed; {} tokens returned",
+        offer.offer_id,
+        seller_key,
+        amount
+    );
+
+    Ok(())
+}
+
+/// **Context for Creating an Offer**
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct CreateOffer<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = 8 + SellerState::INIT_SPACE,
+        seeds = [b"seller_state", token_mint.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_state: Account<'info, SellerState>,
+
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == token_mint.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"offer", seller.key().as_ref(), &offer_id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = offer
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// **Context for Filling an Offer**
+#[derive(Accounts)]
+pub struct FillOffer<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut, address = offer.seller)]
+    pub seller: SystemAccount<'info>,
+
+    #[account(address = offer.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump = trading_pool.bump
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"offer", offer.seller.as_ref(), &offer.offer_id.to_le_bytes()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump = offer.escrow_bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == token_mint.key()
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = Some(buyer_quote_account.mint) == offer.quote_mint @ OtcError::InvalidQuoteAccount
+    )]
+    pub buyer_quote_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = Some(seller_quote_account.mint) == offer.quote_mint @ OtcError::InvalidQuoteAccount,
+        constraint = seller_quote_account.owner == offer.seller @ OtcError::InvalidQuoteAccount
+    )]
+    pub seller_quote_account: Option<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Context for Cancelling or Refunding an Offer**
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(mut, address = offer.seller)]
+    pub seller: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"offer", offer.seller.as_ref(), &offer.offer_id.to_le_bytes()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump = offer.escrow_bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == offer.token_mint,
+        constraint = seller_token_account.owner == offer.seller
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// **OTC Offer (one per seller and offer id)**
+#[account]
+#[derive(InitSpace)]
+pub struct Offer {
+    pub seller: Pubkey,
+    pub token_mint: Pubkey,
+    pub offer_id: u64,
+    pub quote_mint: Option<Pubkey>, // Payment mint; `None` means lamports
+    pub price_per_token: u64,      // Quote base units (or lamports) per whole token
+    pub amount_remaining: u64,     // Tokens still held in escrow
+    pub expires_at: i64,           // After this the offer can no longer be filled
+    pub bump: u8,
+    pub escrow_bump: u8,
+    pub sell_tax_bps: u16,         // Sell tax at posting, charged on each fill
+}
+
+impl Offer {
+    /// Sell tax owed on `amount` escrowed tokens, rounded down
+    pub fn fill_tax(&self, amount: u64) -> Result<u64> {
+        let tax = (amount as u128)
+            .checked_mul(self.sell_tax_bps as u128)
+            .ok_or(OtcError::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        Ok(tax as u64)
+    }
+}
+
+/// **OTC Errors (codes 6400-6499)**
+#[error_code(offset = 6400)]
+pub enum OtcError {
+    #[msg("Offer needs a positive amount and price and a future expiry.")]
+    InvalidOffer,
+    #[msg("Offer has expired.")]
+    OfferExpired,
+    #[msg("Fill amount must be positive and no more than the offer's remaining amount.")]
+    InvalidFillAmount,
+    #[msg("Quote token accounts are missing or do not match the offer.")]
+    InvalidQuoteAccount,
+    #[msg("Only the seller can cancel an offer before it expires.")]
+    Unauthorized,
+    #[msg("Selling of tokens is currently locked by governance.")]
+    SellingLocked,
+    #[msg("Arithmetic overflow in offer calculation.")]
+    MathOverflow,
+    #[msg("Selling is paused by the price-drop circuit breaker.")]
+    SellingPaused,
+}
+
+impl<'info> CreateOffer<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.seller_token_account.to_account_info(),
+            to: self.escrow.to_account_info(),
+            authority: self.seller.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+}
+
+impl<'info> FillOffer<'info> {
+    fn sol_payment_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, system_program::Transfer<'info>> {
+        let cpi_accounts = system_program::Transfer {
+            from: self.buyer.to_account_info(),
+            to: self.seller.to_account_info(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+
+    fn quote_payment_context(&self) -> Result<CpiContext<'_, '_, '_, 'info, Transfer<'info>>> {
+        let (Some(from), Some(to)) = (&self.buyer_quote_account, &self.seller_quote_account) else {
+            return err!(OtcError::InvalidQuoteAccount);
+        };
+        let cpi_accounts = Transfer {
+            from: from.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+        Ok(CpiContext::new(self.token_program.to_account_info(), cpi_accounts))
+    }
+
+    fn release_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.escrow.to_account_info(),
+            to: self.buyer_token_account.to_account_info(),
+            authority: self.offer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn tax_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.escrow.to_account_info(),
+            to: self.reward_vault.to_account_info(),
+            authority: self.offer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn close_escrow_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.seller.to_account_info(),
+            authority: self.offer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> CancelOffer<'info> {
+    fn refund_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.escrow.to_account_info(),
+            to: self.seller_token_account.to_account_info(),
+            authority: self.offer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn close_escrow_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.seller.to_account_info(),
+            authority: self.offer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
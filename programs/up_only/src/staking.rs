@@ -1,98 +1,205 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 use solana_program::clock::Clock;
 
-#[program]
-pub mod staking {
-    use super::*;
+use crate::bonding_curve::BondingCurve;
+
+/// Number of stake checkpoints retained per staker for voting snapshots
+pub const MAX_STAKE_CHECKPOINTS: usize = 16;
+
+/// Fixed-point scale for the per-token reward accumulator
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Create the per-mint staking pool and the vault that holds staked tokens
+pub fn initialize_staking_pool(
+    ctx: Context<InitializeStakingPool>,
+    min_stake_duration: i64,
+) -> Result<()> {
+    require!(min_stake_duration >= 0, StakingError::InvalidStakeDuration);
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    staking_pool.token_mint = ctx.accounts.token_mint.key();
+    staking_pool.authority = ctx.accounts.authority.key();
+    staking_pool.total_staked = 0;
+    staking_pool.min_stake_duration = min_stake_duration;
+    staking_pool.reward_per_token = 0;
+    staking_pool.undistributed_rewards = 0;
+    staking_pool.bump = ctx.bumps.staking_pool;
+
+    msg!(
+        "Staking pool initialized for mint {} (min stake duration: {} seconds)",
+        staking_pool.token_mint,
+        min_stake_duration
+    );
+
+    Ok(())
+}
 
-    /// Function to stake tokens into the staking pool
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
-        let staking_account = &mut ctx.accounts.staking_pool;
-        let clock = Clock::get()?;
+/// Function to stake tokens into the staking pool
+pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // Ensure user has enough tokens to stake
+    require!(
+        ctx.accounts.user_token_account.amount >= amount,
+        StakingError::InsufficientFunds
+    );
+
+    // Transfer tokens from the user's account to the staking pool (signed by the user)
+    token::transfer(ctx.accounts.transfer_context(), amount)?;
+
+    // Record staking details, settling rewards earned on the previous balance first
+    let staker_account = &mut ctx.accounts.staker_account;
+    staker_account.settle_rewards(&ctx.accounts.staking_pool)?;
+    staker_account.staker = ctx.accounts.user.key();
+    staker_account.add_stake(amount, clock.unix_timestamp)?;
+    staker_account.record_checkpoint(clock.slot);
+
+    // Update total staked in the pool
+    let staking_account = &mut ctx.accounts.staking_pool;
+    staking_account.total_staked += amount;
+
+    msg!(
+        "User {} staked {} tokens. Total staked: {}",
+        ctx.accounts.user.key(),
+        amount,
+        staking_account.total_staked
+    );
+
+    Ok(())
+}
 
-        // Ensure user has enough tokens to stake
-        require!(
-            ctx.accounts.user_token_account.amount >= amount,
-            StakingError::InsufficientFunds
-        );
+/// Function to unstake tokens from the staking pool
+pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.staker_account.amount_staked > 0,
+        StakingError::NoStakeFound
+    );
+
+    // Calculate staking duration
+    let staking_duration = clock.unix_timestamp - ctx.accounts.staker_account.stake_start_time;
+
+    // Check for lock period (minimum staking period configured on the pool)
+    require!(
+        staking_duration >= ctx.accounts.staking_pool.min_stake_duration,
+        StakingError::StakeLocked
+    );
+
+    let amount = ctx.accounts.staker_account.amount_staked;
+
+    // Transfer tokens back to the user
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"staking_pool", mint_key.as_ref(), &[ctx.accounts.staking_pool.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        ctx.accounts.transfer_context().with_signer(signer_seeds),
+        amount,
+    )?;
+
+    // Reset user stake record; earned rewards stay claimable
+    let staker_account = &mut ctx.accounts.staker_account;
+    staker_account.settle_rewards(&ctx.accounts.staking_pool)?;
+    staker_account.amount_staked = 0;
+    staker_account.stake_start_time = 0;
+    staker_account.record_checkpoint(clock.slot);
+
+    // Update total staked amount in pool
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    staking_pool.total_staked -= amount;
+
+    msg!(
+        "User {} unstaked {} tokens. Total staked: {}",
+        ctx.accounts.user.key(),
+        amount,
+        staking_pool.total_staked
+    );
+
+    Ok(())
+}
 
-        // Transfer tokens from the user's account to the staking pool
-        let staking_key = ctx.accounts.staking_pool.key();
-let seeds = &[b"staking_pool", staking_key.as_ref(), &[ctx.accounts.staking_pool.bump]];
-        let signer_seeds = &[&seeds[..]];
-        
-        token::transfer(
-            ctx.accounts.transfer_context().with_signer(signer_seeds),
-            amount,
-        )?;
-
-        // Record staking details
-        let staker_account = &mut ctx.accounts.staker_account;
-        staker_account.staker = ctx.accounts.user.key();
-        staker_account.amount_staked += amount;
-        staker_account.stake_start_time = clock.unix_timestamp;
-
-        // Update total staked in the pool
-        staking_account.total_staked += amount;
-
-        msg!(
-            "User {} staked {} tokens. Total staked: {}",
-            ctx.accounts.user.key(),
-            amount,
-            staking_account.total_staked
-        );
+/// Function to withdraw the sell-tax rewards a staker has earned
+pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+    let staker_account = &mut ctx.accounts.staker_account;
+    staker_account.settle_rewards(&ctx.accounts.staking_pool)?;
 
-        Ok(())
-    }
+    let amount = staker_account.pending_rewards;
+    require!(amount > 0, StakingError::NoRewards);
+    staker_account.pending_rewards = 0;
 
-    /// Function to unstake tokens from the staking pool
-    pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
-        let staking_pool = &mut ctx.accounts.staking_pool;
-        let staker_account = &mut ctx.accounts.staker_account;
-        let clock = Clock::get()?;
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"staking_pool", mint_key.as_ref(), &[ctx.accounts.staking_pool.bump]];
+    let signer_seeds = &[&seeds[..]];
 
-        require!(
-            staker_account.amount_staked > 0,
-            StakingError::NoStakeFound
-        );
+    token::transfer(
+        ctx.accounts.transfer_context().with_signer(signer_seeds),
+        amount,
+    )?;
 
-        // Calculate staking duration
-        let staking_duration = clock.unix_timestamp - staker_account.stake_start_time;
+    msg!(
+        "User {} claimed {} reward tokens",
+        ctx.accounts.user.key(),
+        amount
+    );
 
-        // Check for lock period (minimum staking period of 7 days)
-        require!(
-            staking_duration >= 604800, // 7 days in seconds
-            StakingError::StakeLocked
-        );
+    Ok(())
+}
 
-        let amount = staker_account.amount_staked;
-
-        // Transfer tokens back to the user
-        let seeds = &[b"staking_pool", ctx.accounts.staking_pool.key().as_ref(), &[ctx.accounts.staking_pool.bump]];
-        let signer_seeds = &[&seeds[..]];
-        
-        token::transfer(
-            ctx.accounts.transfer_context().with_signer(signer_seeds),
-            amount,
-        )?;
-
-        // Reset user stake record
-        staker_account.amount_staked = 0;
-        staker_account.stake_start_time = 0;
-
-        // Update total staked amount in pool
-        staking_pool.total_staked -= amount;
-
-        msg!(
-            "User {} unstaked {} tokens. Total staked: {}",
-            ctx.accounts.user.key(),
-            amount,
-            staking_pool.total_staked
-        );
+/// **Accounts for Initializing a Staking Pool**
+#[derive(Accounts)]
+pub struct InitializeStakingPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = crate::bonding_curve::is_launch_authority(
+            &token_mint,
+            bonding_curve.as_deref(),
+            authority.key()
+        ) @ StakingError::Unauthorized
+    )]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"bonding_curve", token_mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Option<Account<'info, BondingCurve>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StakingPool::INIT_SPACE,
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
 
-        Ok(())
-    }
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = staking_pool
+    )]
+    pub staking_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reward_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = staking_pool
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 /// **Accounts for Staking**
@@ -101,16 +208,36 @@ pub struct Stake<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    #[account(mut)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump = staking_pool.bump
+    )]
     pub staking_pool: Account<'info, StakingPool>,
 
-    #[account(mut, has_one = user)]
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == token_mint.key()
+    )]
     pub user_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump
+    )]
     pub staking_token_account: Account<'info, TokenAccount>,
 
-    #[account(init_if_needed, payer = user, space = 8 + 32 + 8 + 8)]
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakerAccount::INIT_SPACE,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
     pub staker_account: Account<'info, StakerAccount>,
 
     pub system_program: Program<'info, System>,
@@ -123,39 +250,206 @@ pub struct Unstake<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    #[account(mut)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump = staking_pool.bump
+    )]
     pub staking_pool: Account<'info, StakingPool>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"staking_vault", token_mint.key().as_ref()],
+        bump
+    )]
     pub staking_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut, has_one = user)]
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == token_mint.key()
+    )]
     pub user_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut, has_one = user)]
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = staker_account.staker == user.key()
+    )]
     pub staker_account: Account<'info, StakerAccount>,
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
 }
 
+/// **Accounts for Claiming Rewards**
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    pub user: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == token_mint.key()
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = staker_account.staker == user.key()
+    )]
+    pub staker_account: Account<'info, StakerAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
 /// **Staking Pool Struct**
 #[account]
+#[derive(InitSpace)]
 pub struct StakingPool {
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,
     pub total_staked: u64,
+    pub min_stake_duration: i64, // Seconds a stake must age before it can be withdrawn
+    pub reward_per_token: u128, // Accumulated rewards per staked token, scaled by REWARD_PRECISION
+    pub undistributed_rewards: u64, // Rewards received while nothing was staked
     pub bump: u8, // Added to store PDA bump seed
 }
 
+impl StakingPool {
+    /// Spread `amount` reward tokens (already in the reward vault) across current stakers
+    pub fn distribute_rewards(&mut self, amount: u64) -> Result<()> {
+        let total = amount
+            .checked_add(self.undistributed_rewards)
+            .ok_or(StakingError::MathOverflow)?;
+        if self.total_staked == 0 {
+            self.undistributed_rewards = total;
+            return Ok(());
+        }
+
+        let increment = (total as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(StakingError::MathOverflow)?
+            / self.total_staked as u128;
+        self.reward_per_token = self
+            .reward_per_token
+            .checked_add(increment)
+            .ok_or(StakingError::MathOverflow)?;
+        self.undistributed_rewards = 0;
+        Ok(())
+    }
+}
+
 /// **User's Stake Account**
 #[account]
+#[derive(InitSpace)]
 pub struct StakerAccount {
     pub staker: Pubkey,
     pub amount_staked: u64,
     pub stake_start_time: i64,
+    pub reward_per_token_paid: u128, // Pool accumulator value at the last settlement
+    pub pending_rewards: u64,      // Rewards earned but not yet claimed
+    #[max_len(MAX_STAKE_CHECKPOINTS)]
+    pub checkpoints: Vec<StakeCheckpoint>, // Staked amount history, oldest first
 }
 
-/// **Staking Errors**
-#[error_code]
+/// **Staked amount as of a given slot**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct StakeCheckpoint {
+    pub slot: u64,
+    pub amount: u64,
+    pub stake_start_time: i64,     // Start of the stake held at `slot`, for vote age checks
+}
+
+impl StakerAccount {
+    /// Add `amount` staked at `now`. The stake start moves to the amount-weighted average of
+    /// the existing stake's start and `now`, rounded up, so a top-up neither resets the age of
+    /// the existing stake nor inherits it in full
+    pub fn add_stake(&mut self, amount: u64, now: i64) -> Result<()> {
+        let total = self
+            .amount_staked
+            .checked_add(amount)
+            .ok_or(StakingError::MathOverflow)?;
+        if self.amount_staked == 0 || total == 0 {
+            self.stake_start_time = now;
+        } else {
+            let age = (now - self.stake_start_time).max(0) as u128;
+            let added_age = (age * amount as u128).div_ceil(total as u128);
+            self.stake_start_time += added_age as i64;
+        }
+        self.amount_staked = total;
+        Ok(())
+    }
+
+    /// Record the current staked amount and stake start at `slot`, dropping the oldest entry
+    /// when full
+    pub fn record_checkpoint(&mut self, slot: u64) {
+        let checkpoint = StakeCheckpoint {
+            slot,
+            amount: self.amount_staked,
+            stake_start_time: self.stake_start_time,
+        };
+        if let Some(last) = self.checkpoints.last_mut() {
+            if last.slot == slot {
+                *last = checkpoint;
+                return;
+            }
+        }
+        if self.checkpoints.len() == MAX_STAKE_CHECKPOINTS {
+            self.checkpoints.remove(0);
+        }
+        self.checkpoints.push(checkpoint);
+    }
+
+    /// Credit rewards accrued on the current balance since the last settlement
+    pub fn settle_rewards(&mut self, staking_pool: &StakingPool) -> Result<()> {
+        let delta = staking_pool.reward_per_token - self.reward_per_token_paid;
+        let earned = (self.amount_staked as u128)
+            .checked_mul(delta)
+            .ok_or(StakingError::MathOverflow)?
+            / REWARD_PRECISION;
+        let earned = u64::try_from(earned).map_err(|_| StakingError::MathOverflow)?;
+        self.pending_rewards = self
+            .pending_rewards
+            .checked_add(earned)
+            .ok_or(StakingError::MathOverflow)?;
+        self.reward_per_token_paid = staking_pool.reward_per_token;
+        Ok(())
+    }
+
+    /// Latest checkpoint recorded strictly before `slot`, if one is still retained. `None` for a
+    /// slot before the oldest retained checkpoint, which voting treats as no stake
+    pub fn checkpoint_before(&self, slot: u64) -> Option<StakeCheckpoint> {
+        self.checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.slot < slot)
+            .copied()
+    }
+}
+
+/// **Staking Errors (codes 6100-6199)**
+#[error_code(offset = 6100)]
 pub enum StakingError {
     #[msg("Insufficient funds to stake.")]
     InsufficientFunds,
@@ -163,6 +457,14 @@ pub enum StakingError {
     NoStakeFound,
     #[msg("Tokens are locked. You must wait for the minimum staking period to withdraw.")]
     StakeLocked,
+    #[msg("Only the mint authority can initialize the staking pool.")]
+    Unauthorized,
+    #[msg("Minimum stake duration cannot be negative.")]
+    InvalidStakeDuration,
+    #[msg("No rewards available to claim.")]
+    NoRewards,
+    #[msg("Arithmetic overflow in staking calculation.")]
+    MathOverflow,
 }
 
 /// **Transfer Context Implementation**
@@ -177,6 +479,17 @@ impl<'info> Stake<'info> {
     }
 }
 
+impl<'info> ClaimRewards<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.reward_vault.to_account_info(),
+            to: self.user_token_account.to_account_info(),
+            authority: self.staking_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
 impl<'info> Unstake<'info> {
     fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         let cpi_accounts = Transfer {
@@ -187,3 +500,144 @@ impl<'info> Unstake<'info> {
         CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(total_staked: u64) -> StakingPool {
+        StakingPool {
+            token_mint: Pubkey::default(),
+            authority: Pubkey::default(),
+            total_staked,
+            min_stake_duration: 0,
+            reward_per_token: 0,
+            undistributed_rewards: 0,
+            bump: 0,
+        }
+    }
+
+    fn staker(amount_staked: u64, staking_pool: &StakingPool) -> StakerAccount {
+        StakerAccount {
+            staker: Pubkey::default(),
+            amount_staked,
+            stake_start_time: 0,
+            reward_per_token_paid: staking_pool.reward_per_token,
+            pending_rewards: 0,
+            checkpoints: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn checkpoints_are_looked_up_strictly_before_a_slot() {
+        let staking_pool = pool(0);
+        let mut staker = staker(0, &staking_pool);
+        staker.add_stake(10, 100).unwrap();
+        staker.record_checkpoint(5);
+        staker.add_stake(20, 200).unwrap();
+        staker.record_checkpoint(8);
+        // A second change in the same slot replaces that slot's checkpoint
+        staker.add_stake(5, 200).unwrap();
+        staker.record_checkpoint(8);
+        assert_eq!(staker.checkpoints.len(), 2);
+
+        assert!(staker.checkpoint_before(5).is_none());
+        assert_eq!(staker.checkpoint_before(6).unwrap().amount, 10);
+        assert_eq!(staker.checkpoint_before(8).unwrap().amount, 10);
+        let latest = staker.checkpoint_before(9).unwrap();
+        assert_eq!((latest.slot, latest.amount), (8, 35));
+    }
+
+    #[test]
+    fn oldest_checkpoints_are_evicted_when_full() {
+        let staking_pool = pool(0);
+        let mut staker = staker(0, &staking_pool);
+        for slot in 1..=MAX_STAKE_CHECKPOINTS as u64 + 2 {
+            staker.add_stake(1, slot as i64).unwrap();
+            staker.record_checkpoint(slot);
+        }
+        assert_eq!(staker.checkpoints.len(), MAX_STAKE_CHECKPOINTS);
+        assert_eq!(staker.checkpoints[0].slot, 3);
+
+        // Slots 1 and 2 were evicted, so a snapshot before slot 3 sees no stake
+        assert!(staker.checkpoint_before(3).is_none());
+        assert_eq!(staker.checkpoint_before(4).unwrap().amount, 3);
+        assert_eq!(
+            staker.checkpoint_before(u64::MAX).unwrap().amount,
+            MAX_STAKE_CHECKPOINTS as u64 + 2
+        );
+    }
+
+    #[test]
+    fn top_ups_keep_a_weighted_stake_start() {
+        let staking_pool = pool(0);
+        let mut staker = staker(0, &staking_pool);
+        staker.add_stake(100, 1_000).unwrap();
+        assert_eq!(staker.stake_start_time, 1_000);
+
+        // Tripling the stake 900s later moves the start three quarters of the way
+        staker.add_stake(300, 1_900).unwrap();
+        assert_eq!(staker.amount_staked, 400);
+        assert_eq!(staker.stake_start_time, 1_675);
+
+        // Rounds toward the later start
+        staker.add_stake(1, 1_676).unwrap();
+        assert_eq!(staker.stake_start_time, 1_676);
+        staker.add_stake(0, 5_000).unwrap();
+        assert_eq!(staker.stake_start_time, 1_676);
+    }
+
+    #[test]
+    fn rewards_wait_for_the_first_stake() {
+        let mut staking_pool = pool(0);
+        staking_pool.distribute_rewards(70).unwrap();
+        staking_pool.distribute_rewards(30).unwrap();
+        assert_eq!(staking_pool.reward_per_token, 0);
+        assert_eq!(staking_pool.undistributed_rewards, 100);
+
+        // The held rewards go to whoever is staked at the next distribution
+        staking_pool.total_staked = 50;
+        let mut first = staker(50, &staking_pool);
+        staking_pool.distribute_rewards(0).unwrap();
+        assert_eq!(staking_pool.undistributed_rewards, 0);
+        first.settle_rewards(&staking_pool).unwrap();
+        assert_eq!(first.pending_rewards, 100);
+    }
+
+    #[test]
+    fn rewards_split_by_stake_and_round_down() {
+        let mut staking_pool = pool(3);
+        let mut small = staker(1, &staking_pool);
+        let mut large = staker(2, &staking_pool);
+
+        staking_pool.distribute_rewards(10).unwrap();
+        assert_eq!(staking_pool.reward_per_token, 10 * REWARD_PRECISION / 3);
+
+        // 10 / 3 per token, rounded down for each staker, so never more than was distributed
+        small.settle_rewards(&staking_pool).unwrap();
+        large.settle_rewards(&staking_pool).unwrap();
+        assert_eq!(small.pending_rewards, 3);
+        assert_eq!(large.pending_rewards, 6);
+
+        // Settling again without new rewards credits nothing
+        small.settle_rewards(&staking_pool).unwrap();
+        assert_eq!(small.pending_rewards, 3);
+    }
+
+    #[test]
+    fn late_stakers_only_earn_later_rewards() {
+        let mut staking_pool = pool(100);
+        let mut early = staker(100, &staking_pool);
+        staking_pool.distribute_rewards(1_000).unwrap();
+
+        early.settle_rewards(&staking_pool).unwrap();
+        staking_pool.total_staked += 100;
+        let mut late = staker(100, &staking_pool);
+        staking_pool.distribute_rewards(1_000).unwrap();
+
+        early.settle_rewards(&staking_pool).unwrap();
+        late.settle_rewards(&staking_pool).unwrap();
+        assert_eq!(early.pending_rewards, 1_500);
+        assert_eq!(late.pending_rewards, 500);
+    }
+}
@@ -1,46 +1,43 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
-use solana_program::program_option::COption;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
 
-#[program]
-pub mod token_creation {
-    use super::*;
+/// Create a new SPL token; mint and freeze authority are assigned by the `init` constraint
+pub fn create_token(
+    ctx: Context<CreateToken>,
+    name: String,
+    symbol: String,
+    decimals: u8,
+) -> Result<()> {
+    msg!(
+        "Token Created: {} ({}) with {} decimals, mint {}",
+        name,
+        symbol,
+        decimals,
+        ctx.accounts.mint.key()
+    );
 
-    // ✅ Ensure function is public
-    pub fn create_token(
-        ctx: Context<CreateToken>,
-        name: String,
-        symbol: String,
-        decimals: u8,
-    ) -> Result<()> {
-        let mint = &mut ctx.accounts.mint;
-        mint.mint_authority = COption::Some(ctx.accounts.authority.key());
-        mint.freeze_authority = COption::Some(ctx.accounts.authority.key());
-
-        msg!("Token Created: {} ({}) with {} decimals", name, symbol, decimals);
-
-        Ok(())
-    }
+    Ok(())
+}
 
-    /// Function to mint tokens to a specified account
-    pub fn mint_tokens(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
-        let cpi_accounts = token::MintTo {
-            mint: ctx.accounts.mint.to_account_info(),
-            to: ctx.accounts.token_account.to_account_info(),
-            authority: ctx.accounts.mint_authority.to_account_info(),
-        };
+/// Function to mint tokens to a specified account
+pub fn mint_tokens(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
+    let cpi_accounts = token::MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.token_account.to_account_info(),
+        authority: ctx.accounts.mint_authority.to_account_info(),
+    };
 
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        token::mint_to(cpi_ctx, amount)?;
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::mint_to(cpi_ctx, amount)?;
 
-        msg!("Minted {} tokens to {}", amount, ctx.accounts.token_account.key());
+    msg!("Minted {} tokens to {}", amount, ctx.accounts.token_account.key());
 
-        Ok(())
-    }
+    Ok(())
 }
 
 /// Accounts required to create a new token
 #[derive(Accounts)]
+#[instruction(name: String, symbol: String, decimals: u8)]
 pub struct CreateToken<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -1,181 +1,2569 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
 
-#[program]
-pub mod trading {
-    use super::*;
+use crate::bonding_curve::{self, BondingCurve};
+use crate::fees::{self, ProtocolConfig, TradeFees};
+use crate::governance::{LockAccount, PauseReason, BPS_DENOMINATOR};
+use crate::staking::StakingPool;
 
-    /// Allows a user to buy tokens from a seller
-    pub fn buy_tokens(ctx: Context<BuyTokens>, amount: u64) -> Result<()> {
-        let token_mint = &ctx.accounts.token_mint;
-        let seller_token_account = &ctx.accounts.seller_token_account;
-        let buyer_token_account = &ctx.accounts.buyer_token_account;
+/// Fixed-point scale of pool prices (quote base units per token base unit)
+pub const PRICE_PRECISION: u128 = 1_000_000_000_000;
 
-        // Ensure seller has enough tokens
-        require!(
-            seller_token_account.amount >= amount,
-            TradingError::InsufficientSupply
-        );
+/// Decimals of every pool's LP token mint
+pub const LP_MINT_DECIMALS: u8 = 9;
+
+/// Shares withheld from the first deposit into a pool and never minted as LP tokens, so the
+/// pool can never be fully drained and its share price reset
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+/// LP fee on pools created by bonding curve graduation (0.3%)
+pub const DEFAULT_LP_FEE_BPS: u16 = 30;
+
+/// Minimum spacing between stored price observations (5 minutes)
+pub const PRICE_OBSERVATION_INTERVAL: i64 = 300;
+
+/// Number of price observations retained per pool, bounding the longest TWAP period
+pub const MAX_PRICE_OBSERVATIONS: usize = 48;
+
+/// Longest automatic sell pause a circuit breaker may impose (1 day)
+pub const MAX_CIRCUIT_BREAKER_PAUSE: i64 = 86400;
+
+/// Longest TWAP window a circuit breaker may compare against, as covered by the retained
+/// price observations (4 hours)
+pub const MAX_CIRCUIT_BREAKER_WINDOW: i64 =
+    MAX_PRICE_OBSERVATIONS as i64 * PRICE_OBSERVATION_INTERVAL;
+
+/// Resting orders an order book tracks per side, so matching can always find the best one
+pub const MAX_OPEN_ORDERS: usize = 32;
+
+/// Number of time buckets a wallet's rolling sell window is tracked in
+pub const SELL_WINDOW_BUCKETS: usize = 8;
+
+/// Create the per-mint trading pool that tracks volume and the creator fee for a launched token
+pub fn initialize_trading_pool(
+    ctx: Context<InitializeTradingPool>,
+    creator_fee_bps: u16,
+) -> Result<()> {
+    fees::validate_fee(creator_fee_bps)?;
+
+    let trading_pool = &mut ctx.accounts.trading_pool;
+    trading_pool.token_mint = ctx.accounts.token_mint.key();
+    trading_pool.authority = ctx.accounts.authority.key();
+    trading_pool.total_traded = 0;
+    trading_pool.creator_fee_bps = creator_fee_bps;
+    trading_pool.bump = ctx.bumps.trading_pool;
+
+    msg!("Trading pool initialized for mint {}", trading_pool.token_mint);
+
+    Ok(())
+}
+
+/// Create the constant-product pool pairing a launched mint with a quote mint, with PDA-owned vaults
+/// and an LP token mint; `lp_fee_bps` of every swap stays in the pool for liquidity providers
+pub fn initialize_pool(ctx: Context<InitializePool>, lp_fee_bps: u16) -> Result<()> {
+    fees::validate_fee(lp_fee_bps)?;
+
+    let liquidity_pool = &mut ctx.accounts.liquidity_pool;
+    liquidity_pool.token_mint = ctx.accounts.token_mint.key();
+    liquidity_pool.quote_mint = ctx.accounts.quote_mint.key();
+    liquidity_pool.lp_mint = ctx.accounts.lp_mint.key();
+    liquidity_pool.token_reserve = 0;
+    liquidity_pool.quote_reserve = 0;
+    liquidity_pool.total_shares = 0;
+    liquidity_pool.lp_fee_bps = lp_fee_bps;
+    liquidity_pool.accrued_lp_fees = 0;
+    liquidity_pool.price_cumulative = 0;
+    liquidity_pool.last_price_update = Clock::get()?.unix_timestamp;
+    liquidity_pool.observations = Vec::new();
+    liquidity_pool.bump = ctx.bumps.liquidity_pool;
+
+    msg!(
+        "Liquidity pool initialized for mint {} against quote mint {}",
+        liquidity_pool.token_mint,
+        liquidity_pool.quote_mint
+    );
+
+    Ok(())
+}
+
+/// Deposit both sides of the pool at the current ratio, up to the given maximums, for LP tokens
+pub fn add_liquidity(
+    ctx: Context<AddLiquidity>,
+    max_token_amount: u64,
+    max_quote_amount: u64,
+) -> Result<()> {
+    require!(
+        max_token_amount > 0 && max_quote_amount > 0,
+        TradingError::ZeroAmount
+    );
+
+    let liquidity_pool = &ctx.accounts.liquidity_pool;
+    let (token_amount, quote_amount, shares, lp_amount) =
+        liquidity_pool.deposit_amounts(max_token_amount, max_quote_amount)?;
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"liquidity_pool", mint_key.as_ref(), &[liquidity_pool.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(ctx.accounts.deposit_token_context(), token_amount)?;
+    token::transfer(ctx.accounts.deposit_quote_context(), quote_amount)?;
+    token::mint_to(
+        ctx.accounts.mint_lp_context().with_signer(signer_seeds),
+        lp_amount,
+    )?;
+
+    let liquidity_pool = &mut ctx.accounts.liquidity_pool;
+    liquidity_pool.record_price(Clock::get()?.unix_timestamp);
+    liquidity_pool.token_reserve = checked_add(liquidity_pool.token_reserve, token_amount)?;
+    liquidity_pool.quote_reserve = checked_add(liquidity_pool.quote_reserve, quote_amount)?;
+    liquidity_pool.total_shares = checked_add(liquidity_pool.total_shares, shares)?;
+
+    msg!(
+        "User {} added {} tokens and {} quote for {} LP tokens",
+        ctx.accounts.provider.key(),
+        token_amount,
+        quote_amount,
+        lp_amount
+    );
+
+    Ok(())
+}
+
+/// Burn LP tokens and withdraw the matching portion of both reserves, including the LP fees
+/// accrued to them
+pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, shares: u64) -> Result<()> {
+    require!(shares > 0, TradingError::ZeroAmount);
+    require!(
+        ctx.accounts.provider_lp_account.amount >= shares,
+        TradingError::InsufficientShares
+    );
+
+    let liquidity_pool = &ctx.accounts.liquidity_pool;
+    let (token_amount, quote_amount) = liquidity_pool.withdrawal_amounts(shares)?;
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"liquidity_pool", mint_key.as_ref(), &[liquidity_pool.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::burn(ctx.accounts.burn_lp_context(), shares)?;
+    token::transfer(
+        ctx.accounts.withdraw_token_context().with_signer(signer_seeds),
+        token_amount,
+    )?;
+    token::transfer(
+        ctx.accounts.withdraw_quote_context().with_signer(signer_seeds),
+        quote_amount,
+    )?;
+
+    let liquidity_pool = &mut ctx.accounts.liquidity_pool;
+    liquidity_pool.record_price(Clock::get()?.unix_timestamp);
+    liquidity_pool.token_reserve -= token_amount;
+    liquidity_pool.quote_reserve -= quote_amount;
+    liquidity_pool.total_shares -= shares;
+
+    msg!(
+        "User {} burned {} LP tokens for {} tokens and {} quote",
+        ctx.accounts.provider.key(),
+        shares,
+        token_amount,
+        quote_amount
+    );
+
+    Ok(())
+}
+
+/// Buy tokens from the liquidity pool with quote at the constant-product price, receiving at
+/// least `min_amount_out` tokens before `deadline`
+pub fn swap_quote_for_tokens(
+    ctx: Context<SwapQuoteForTokens>,
+    quote_amount: u64,
+    min_amount_out: u64,
+    deadline: i64,
+) -> Result<()> {
+    require_before_deadline(deadline)?;
+    require!(quote_amount > 0, TradingError::ZeroAmount);
+
+    // Fees come off the quote paid in; the rest is priced against the pool
+    let liquidity_pool = &ctx.accounts.liquidity_pool;
+    let fees = TradeFees::on_gross(
+        quote_amount,
+        ctx.accounts.protocol_config.protocol_fee_bps,
+        ctx.accounts.trading_pool.creator_fee_bps,
+        liquidity_pool.lp_fee_bps,
+    )?;
+    let token_amount = get_amount_out(
+        quote_amount - fees.total(),
+        liquidity_pool.quote_reserve,
+        liquidity_pool.token_reserve,
+    )?;
+    require!(
+        token_amount >= min_amount_out,
+        TradingError::SlippageExceeded
+    );
+
+    execute_buy(ctx, quote_amount - fees.total(), fees, token_amount)
+}
+
+/// Buy exactly `token_amount` tokens from the liquidity pool, paying at most `max_amount_in`
+/// quote before `deadline`; only the required quote is pulled
+pub fn swap_quote_for_exact_tokens(
+    ctx: Context<SwapQuoteForTokens>,
+    token_amount: u64,
+    max_amount_in: u64,
+    deadline: i64,
+) -> Result<()> {
+    require_before_deadline(deadline)?;
+    require!(token_amount > 0, TradingError::ZeroAmount);
+
+    // Quote the pool needs, grossed up so it remains after fees
+    let liquidity_pool = &ctx.accounts.liquidity_pool;
+    let quote_amount = get_amount_in(
+        token_amount,
+        liquidity_pool.quote_reserve,
+        liquidity_pool.token_reserve,
+    )?;
+    let (gross_amount, fees) = TradeFees::gross_up(
+        quote_amount,
+        ctx.accounts.protocol_config.protocol_fee_bps,
+        ctx.accounts.trading_pool.creator_fee_bps,
+        liquidity_pool.lp_fee_bps,
+    )?;
+    require!(
+        gross_amount <= max_amount_in,
+        TradingError::SlippageExceeded
+    );
+
+    execute_buy(ctx, quote_amount, fees, token_amount)
+}
+
+/// Move a priced buy and its fees through the pool, treasury and creator vaults and record it;
+/// the LP fee is deposited along with `quote_amount`
+fn execute_buy(
+    ctx: Context<SwapQuoteForTokens>,
+    quote_amount: u64,
+    fees: TradeFees,
+    token_amount: u64,
+) -> Result<()> {
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"liquidity_pool", mint_key.as_ref(), &[ctx.accounts.liquidity_pool.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        ctx.accounts.deposit_quote_context(),
+        quote_amount + fees.liquidity,
+    )?;
+    if fees.protocol > 0 {
+        token::transfer(ctx.accounts.protocol_fee_context(), fees.protocol)?;
+    }
+    if fees.creator > 0 {
+        token::transfer(ctx.accounts.creator_fee_context(), fees.creator)?;
+    }
+    token::transfer(
+        ctx.accounts.withdraw_token_context().with_signer(signer_seeds),
+        token_amount,
+    )?;
+
+    let liquidity_pool = &mut ctx.accounts.liquidity_pool;
+    liquidity_pool.record_price(Clock::get()?.unix_timestamp);
+    liquidity_pool.quote_reserve =
+        checked_add(liquidity_pool.quote_reserve, quote_amount + fees.liquidity)?;
+    liquidity_pool.token_reserve -= token_amount;
+    liquidity_pool.accrued_lp_fees = checked_add(liquidity_pool.accrued_lp_fees, fees.liquidity)?;
+
+    let trading_pool = &mut ctx.accounts.trading_pool;
+    trading_pool.total_traded = checked_add(trading_pool.total_traded, token_amount)?;
+
+    msg!(
+        "User {} bought {} tokens for {} quote (fees: {} protocol, {} creator, {} LP). Total traded in pool: {}",
+        ctx.accounts.buyer.key(),
+        token_amount,
+        quote_amount + fees.total(),
+        fees.protocol,
+        fees.creator,
+        fees.liquidity,
+        trading_pool.total_traded
+    );
+
+    Ok(())
+}
+
+/// Allows a user to sell tokens into the liquidity pool for at least `min_amount_out` quote
+/// before `deadline`, if selling is not locked
+pub fn sell_tokens(
+    ctx: Context<SellTokens>,
+    amount: u64,
+    min_amount_out: u64,
+    deadline: i64,
+) -> Result<()> {
+    require_before_deadline(deadline)?;
+
+    // Split off the sell tax, escalated while a lock vote is pending
+    let now = Clock::get()?.unix_timestamp;
+    let tax = sell_tax(&ctx.accounts.lock_account, amount, now)?;
+
+    // Price the remaining tokens against the pool reserves; fees come off the quote paid out
+    let liquidity_pool = &ctx.accounts.liquidity_pool;
+    let quote_amount = get_amount_out(
+        amount - tax,
+        liquidity_pool.token_reserve,
+        liquidity_pool.quote_reserve,
+    )?;
+    let fees = TradeFees::on_gross(
+        quote_amount,
+        ctx.accounts.protocol_config.protocol_fee_bps,
+        ctx.accounts.trading_pool.creator_fee_bps,
+        liquidity_pool.lp_fee_bps,
+    )?;
+    require!(
+        quote_amount - fees.total() >= min_amount_out,
+        TradingError::SlippageExceeded
+    );
+
+    execute_sell(ctx, amount, tax, quote_amount, fees, now)
+}
+
+/// Sell just enough tokens, tax and fees included, to receive exactly `quote_amount` from the
+/// liquidity pool, spending at most `max_amount_in` tokens before `deadline`
+pub fn sell_tokens_for_exact_quote(
+    ctx: Context<SellTokens>,
+    quote_amount: u64,
+    max_amount_in: u64,
+    deadline: i64,
+) -> Result<()> {
+    require_before_deadline(deadline)?;
+    require!(quote_amount > 0, TradingError::ZeroAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let (amount, tax, gross_amount, fees) = price_exact_quote_sell(
+        &ctx.accounts.liquidity_pool,
+        quote_amount,
+        ctx.accounts.lock_account.sell_tax_bps(now),
+        ctx.accounts.protocol_config.protocol_fee_bps,
+        ctx.accounts.trading_pool.creator_fee_bps,
+    )?;
+    require!(amount <= max_amount_in, TradingError::SlippageExceeded);
+
+    execute_sell(ctx, amount, tax, gross_amount, fees, now)
+}
+
+/// Price a sell that pays out exactly `quote_amount` after fees: the tokens to sell, the sell
+/// tax among them, and the gross quote taken from the pool with its fees
+fn price_exact_quote_sell(
+    liquidity_pool: &LiquidityPool,
+    quote_amount: u64,
+    tax_bps: u16,
+    protocol_fee_bps: u16,
+    creator_fee_bps: u16,
+) -> Result<(u64, u64, u64, TradeFees)> {
+    require!(tax_bps < BPS_DENOMINATOR, TradingError::SellTaxTooHigh);
 
-        // Transfer tokens from seller to buyer
+    let (gross_amount, fees) = TradeFees::gross_up(
+        quote_amount,
+        protocol_fee_bps,
+        creator_fee_bps,
+        liquidity_pool.lp_fee_bps,
+    )?;
+    let proceeds = get_amount_in(
+        gross_amount,
+        liquidity_pool.token_reserve,
+        liquidity_pool.quote_reserve,
+    )?;
+    // Tokens the pool must receive, grossed up so they remain after the sell tax
+    let amount = to_u64(
+        (proceeds as u128 * BPS_DENOMINATOR as u128)
+            .div_ceil((BPS_DENOMINATOR - tax_bps) as u128),
+    )?;
+    Ok((amount, amount - proceeds, gross_amount, fees))
+}
+
+/// Apply the lock and sell policy to a priced sell, then move it and its fees through the pool,
+/// treasury and creator vaults and route its tax to stakers; the LP fee stays in the pool
+fn execute_sell(
+    ctx: Context<SellTokens>,
+    amount: u64,
+    tax: u64,
+    quote_amount: u64,
+    fees: TradeFees,
+    now: i64,
+) -> Result<()> {
+    // Ensure that selling is not locked; a lock whose window has elapsed no longer applies
+    require!(
+        !ctx.accounts.lock_account.is_active(now),
+        TradingError::SellingLocked
+    );
+    require!(
+        !ctx.accounts.lock_account.is_paused(now),
+        TradingError::SellingPaused
+    );
+
+    // Ensure seller has enough tokens
+    require!(
+        ctx.accounts.seller_token_account.amount >= amount,
+        TradingError::InsufficientBalance
+    );
+
+    // Apply the graduated sell limits configured by governance
+    let seller_state = &mut ctx.accounts.seller_state;
+    if seller_state.seller == Pubkey::default() {
+        seller_state.token_mint = ctx.accounts.token_mint.key();
+        seller_state.seller = ctx.accounts.seller.key();
+        seller_state.bump = ctx.bumps.seller_state;
+    }
+    enforce_sell_policy(
+        &mut ctx.accounts.lock_account,
+        seller_state,
+        ctx.accounts.seller_token_account.amount,
+        amount,
+        now,
+        Clock::get()?.slot,
+    )?;
+
+    // Move the reserves to their post-trade values first, so the circuit breaker judges the
+    // price this sell leaves behind before any tokens move
+    let proceeds = amount - tax;
+    let liquidity_pool = &mut ctx.accounts.liquidity_pool;
+    liquidity_pool.record_price(now);
+    liquidity_pool.token_reserve = checked_add(liquidity_pool.token_reserve, proceeds)?;
+    liquidity_pool.quote_reserve -= quote_amount - fees.liquidity;
+    liquidity_pool.accrued_lp_fees = checked_add(liquidity_pool.accrued_lp_fees, fees.liquidity)?;
+
+    // A sell that pushes the price too far below its TWAP pauses the sells that follow it
+    let circuit_breaker = ctx.accounts.lock_account.circuit_breaker;
+    if let Some(twap) = circuit_breaker.tripped(liquidity_pool, now)? {
+        let lock_account = &mut ctx.accounts.lock_account;
+        lock_account.paused_until = now + circuit_breaker.pause_duration;
+        lock_account.pause_reason = PauseReason::PriceDrop;
+
+        emit!(SellingPaused {
+            token_mint: lock_account.token_mint,
+            reason: PauseReason::PriceDrop,
+            price: liquidity_pool.spot_price(),
+            twap,
+            paused_until: lock_account.paused_until,
+        });
+    }
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"liquidity_pool", mint_key.as_ref(), &[ctx.accounts.liquidity_pool.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    // Swap the seller's tokens for quote and route the tax to stakers
+    token::transfer(ctx.accounts.transfer_context(), proceeds)?;
+    token::transfer(
+        ctx.accounts.withdraw_quote_context().with_signer(signer_seeds),
+        quote_amount - fees.total(),
+    )?;
+    if fees.protocol > 0 {
+        token::transfer(
+            ctx.accounts.protocol_fee_context().with_signer(signer_seeds),
+            fees.protocol,
+        )?;
+    }
+    if fees.creator > 0 {
         token::transfer(
-            ctx.accounts
-                .transfer_context()
-                .with_signer(&[&ctx.accounts.seller.key().as_ref()]),
-            amount,
+            ctx.accounts.creator_fee_context().with_signer(signer_seeds),
+            fees.creator,
         )?;
+    }
+    if tax > 0 {
+        token::transfer(ctx.accounts.tax_transfer_context(), tax)?;
+        ctx.accounts.staking_pool.distribute_rewards(tax)?;
+    }
 
-        // Update trading pool metrics
-        let trading_pool = &mut ctx.accounts.trading_pool;
-        trading_pool.total_traded += amount;
+    // Update trading pool total traded amount
+    let trading_pool = &mut ctx.accounts.trading_pool;
+    trading_pool.total_traded = checked_add(trading_pool.total_traded, amount)?;
 
-        msg!(
-            "User {} bought {} tokens from {}",
-            ctx.accounts.buyer.key(),
-            amount,
-            ctx.accounts.seller.key()
-        );
+    msg!(
+        "User {} sold {} tokens for {} quote (tax: {}, fees: {} protocol, {} creator, {} LP). Total traded in pool: {}",
+        ctx.accounts.seller.key(),
+        amount,
+        quote_amount - fees.total(),
+        tax,
+        fees.protocol,
+        fees.creator,
+        fees.liquidity,
+        trading_pool.total_traded
+    );
 
-        Ok(())
+    Ok(())
+}
+
+/// Time-weighted average pool price over the last `period` seconds, scaled by `PRICE_PRECISION`;
+/// also returned to the caller as instruction return data
+pub fn get_twap(ctx: Context<GetTwap>, period: i64) -> Result<u128> {
+    let now = Clock::get()?.unix_timestamp;
+    let twap = ctx.accounts.liquidity_pool.twap(period, now)?;
+
+    msg!(
+        "TWAP for mint {} over {} seconds: {}",
+        ctx.accounts.token_mint.key(),
+        period,
+        twap
+    );
+
+    Ok(twap)
+}
+
+/// Create the per-mint limit order book, quoted in the liquidity pool's quote mint
+pub fn initialize_order_book(ctx: Context<InitializeOrderBook>) -> Result<()> {
+    let order_book = &mut ctx.accounts.order_book;
+    order_book.token_mint = ctx.accounts.token_mint.key();
+    order_book.quote_mint = ctx.accounts.quote_mint.key();
+    order_book.next_order_id = 0;
+    order_book.bids = Vec::new();
+    order_book.asks = Vec::new();
+    order_book.bump = ctx.bumps.order_book;
+
+    msg!("Order book initialized for mint {}", order_book.token_mint);
+
+    Ok(())
+}
+
+/// Rest a limit order on the book, escrowing the tokens of an ask or the quote of a bid
+pub fn place_order(ctx: Context<PlaceOrder>, side: OrderSide, price: u64, amount: u64) -> Result<()> {
+    require!(price > 0 && amount > 0, TradingError::ZeroAmount);
+
+    let (amount, quote_locked) = match side {
+        OrderSide::Ask => {
+            // Asks are sells, so they respect the governance lock, pauses, sell policy and tax
+            // like `sell_tokens`; the tax is taken up front and only the rest rests on the book
+            let now = Clock::get()?.unix_timestamp;
+            require!(
+                !ctx.accounts.lock_account.is_active(now),
+                TradingError::SellingLocked
+            );
+            require!(
+                !ctx.accounts.lock_account.is_paused(now),
+                TradingError::SellingPaused
+            );
+            require!(
+                ctx.accounts.owner_token_account.amount >= amount,
+                TradingError::InsufficientBalance
+            );
+
+            let tax = sell_tax(&ctx.accounts.lock_account, amount, now)?;
+            require!(amount > tax, TradingError::ZeroAmount);
+
+            let seller_state = &mut ctx.accounts.seller_state;
+            if seller_state.seller == Pubkey::default() {
+                seller_state.token_mint = ctx.accounts.token_mint.key();
+                seller_state.seller = ctx.accounts.owner.key();
+                seller_state.bump = ctx.bumps.seller_state;
+            }
+            enforce_sell_policy(
+                &mut ctx.accounts.lock_account,
+                seller_state,
+                ctx.accounts.owner_token_account.amount,
+                amount,
+                now,
+                Clock::get()?.slot,
+            )?;
+
+            token::transfer(ctx.accounts.escrow_token_context(), amount - tax)?;
+            if tax > 0 {
+                token::transfer(ctx.accounts.tax_transfer_context(), tax)?;
+                ctx.accounts.staking_pool.distribute_rewards(tax)?;
+            }
+            (amount - tax, 0)
+        }
+        OrderSide::Bid => {
+            let unit = bonding_curve::token_unit(ctx.accounts.token_mint.decimals)?;
+            let quote_locked = to_u64((amount as u128 * price as u128).div_ceil(unit))?;
+            token::transfer(ctx.accounts.escrow_quote_context(), quote_locked)?;
+            (amount, quote_locked)
+        }
+    };
+
+    let order_book = &mut ctx.accounts.order_book;
+    let order = &mut ctx.accounts.order;
+    order.order_book = order_book.key();
+    order.owner = ctx.accounts.owner.key();
+    order.order_id = order_book.next_order_id;
+    order.side = side;
+    order.price = price;
+    order.amount_remaining = amount;
+    order.quote_locked = quote_locked;
+    order.bump = ctx.bumps.order;
+    order_book.insert(side, order.order_id, price)?;
+    order_book.next_order_id += 1;
+
+    msg!(
+        "User {} placed {:?} order {} for {} tokens at {}",
+        order.owner,
+        side,
+        order.order_id,
+        amount,
+        price
+    );
+
+    Ok(())
+}
+
+/// Remove an open order and return whatever it still has in escrow
+pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    ctx.accounts.order_book.remove(order.side, order.order_id);
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"order_book", mint_key.as_ref(), &[ctx.accounts.order_book.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    match order.side {
+        OrderSide::Ask => token::transfer(
+            ctx.accounts.refund_token_context().with_signer(signer_seeds),
+            order.amount_remaining,
+        )?,
+        OrderSide::Bid => token::transfer(
+            ctx.accounts.refund_quote_context().with_signer(signer_seeds),
+            order.quote_locked,
+        )?,
+    }
+
+    msg!("User {} cancelled order {}", order.owner, order.order_id);
+
+    Ok(())
+}
+
+/// Cross the best bid with the best ask at the earlier order's price; callable by anyone. Fully
+/// filled orders are closed and a bid's unspent quote is returned to its owner
+pub fn match_orders(ctx: Context<MatchOrders>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !ctx.accounts.lock_account.is_active(now),
+        TradingError::SellingLocked
+    );
+    require!(
+        !ctx.accounts.lock_account.is_paused(now),
+        TradingError::SellingPaused
+    );
+
+    let bid = &ctx.accounts.bid;
+    let ask = &ctx.accounts.ask;
+    require!(
+        bid.side == OrderSide::Bid && ask.side == OrderSide::Ask,
+        TradingError::InvalidOrderSide
+    );
+    require!(bid.price >= ask.price, TradingError::OrdersDoNotCross);
+
+    // Only the best order on each side may fill: highest bid and lowest ask, oldest first
+    let order_book = &ctx.accounts.order_book;
+    require!(
+        order_book.best(OrderSide::Bid) == Some(bid.order_id)
+            && order_book.best(OrderSide::Ask) == Some(ask.order_id),
+        TradingError::NotBestOrder
+    );
+
+    let price = if bid.order_id < ask.order_id {
+        bid.price
+    } else {
+        ask.price
+    };
+    let amount = bid.amount_remaining.min(ask.amount_remaining);
+    let unit = bonding_curve::token_unit(ctx.accounts.token_mint.decimals)?;
+    let quote_amount = to_u64(amount as u128 * price as u128 / unit)?;
+    require!(quote_amount > 0, TradingError::ZeroAmount);
+
+    // Fees come off the quote paid to the ask, as on a pool sell; no LP fee, as no pool is used
+    let fees = TradeFees::on_gross(
+        quote_amount,
+        ctx.accounts.protocol_config.protocol_fee_bps,
+        ctx.accounts.trading_pool.creator_fee_bps,
+        0,
+    )?;
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let seeds = &[b"order_book", mint_key.as_ref(), &[ctx.accounts.order_book.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        ctx.accounts.deliver_token_context().with_signer(signer_seeds),
+        amount,
+    )?;
+    token::transfer(
+        ctx.accounts.pay_ask_context().with_signer(signer_seeds),
+        quote_amount - fees.total(),
+    )?;
+    if fees.protocol > 0 {
+        token::transfer(
+            ctx.accounts.protocol_fee_context().with_signer(signer_seeds),
+            fees.protocol,
+        )?;
+    }
+    if fees.creator > 0 {
+        token::transfer(
+            ctx.accounts.creator_fee_context().with_signer(signer_seeds),
+            fees.creator,
+        )?;
+    }
+
+    let trading_pool = &mut ctx.accounts.trading_pool;
+    trading_pool.total_traded = checked_add(trading_pool.total_traded, amount)?;
+
+    let bid = &mut ctx.accounts.bid;
+    bid.amount_remaining -= amount;
+    bid.quote_locked -= quote_amount;
+    let ask = &mut ctx.accounts.ask;
+    ask.amount_remaining -= amount;
+
+    msg!(
+        "Matched bid {} with ask {}: {} tokens at {}",
+        bid.order_id,
+        ask.order_id,
+        amount,
+        price
+    );
+
+    if ctx.accounts.bid.amount_remaining == 0 {
+        let bid_id = ctx.accounts.bid.order_id;
+        ctx.accounts.order_book.remove(OrderSide::Bid, bid_id);
+        let refund = ctx.accounts.bid.quote_locked;
+        if refund > 0 {
+            token::transfer(
+                ctx.accounts.refund_bid_context().with_signer(signer_seeds),
+                refund,
+            )?;
+        }
+        ctx.accounts
+            .bid
+            .close(ctx.accounts.bid_owner.to_account_info())?;
+    }
+    if ctx.accounts.ask.amount_remaining == 0 {
+        let ask_id = ctx.accounts.ask.order_id;
+        ctx.accounts.order_book.remove(OrderSide::Ask, ask_id);
+        ctx.accounts
+            .ask
+            .close(ctx.accounts.ask_owner.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+/// Sell tax owed on `amount` tokens, escalated while a lock vote is pending
+pub(crate) fn sell_tax(lock_account: &LockAccount, amount: u64, now: i64) -> Result<u64> {
+    let tax_bps = lock_account.sell_tax_bps(now);
+    to_u64(
+        (amount as u128)
+            .checked_mul(tax_bps as u128)
+            .ok_or(TradingError::MathOverflow)?
+            / BPS_DENOMINATOR as u128,
+    )
+}
+
+/// Check a sell against the lock account's sell policy and record it in the trackers
+pub(crate) fn enforce_sell_policy(
+    lock_account: &mut LockAccount,
+    seller_state: &mut SellerState,
+    holdings: u64,
+    amount: u64,
+    now: i64,
+    slot: u64,
+) -> Result<()> {
+    let policy = lock_account.sell_policy;
+
+    // Cooldown since the wallet's previous sell
+    if seller_state.sell_count > 0 {
+        require!(
+            now >= seller_state.last_sell_time + policy.sell_cooldown,
+            TradingError::SellCooldownActive
+        );
     }
 
-    /// Allows a user to sell tokens into the trading pool if selling is not locked
-    pub fn sell_tokens(ctx: Context<SellTokens>, amount: u64) -> Result<()> {
-        let trading_pool = &mut ctx.accounts.trading_pool;
-        let governance_account = &ctx.accounts.governance;
+    // Share of the wallet's current balance
+    if policy.max_sell_bps_of_holdings > 0 {
+        let scaled_amount = (amount as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .ok_or(TradingError::MathOverflow)?;
+        let scaled_limit = (holdings as u128)
+            .checked_mul(policy.max_sell_bps_of_holdings as u128)
+            .ok_or(TradingError::MathOverflow)?;
+        require!(
+            scaled_amount <= scaled_limit,
+            TradingError::HoldingsSellLimitExceeded
+        );
+    }
 
-        // Ensure that selling is not locked
+    // Per-wallet amount within the rolling window ending now. Sells are recorded even without
+    // a limit, so one enabled later already sees the wallet's recent sells
+    if policy.max_sell_per_window > 0 {
+        let sold_in_window = seller_state
+            .sold_within(policy.sell_window, now)?
+            .checked_add(amount)
+            .ok_or(TradingError::MathOverflow)?;
         require!(
-            !governance_account.selling_locked,
-            TradingError::SellingLocked
+            sold_in_window <= policy.max_sell_per_window,
+            TradingError::WalletSellLimitExceeded
         );
+    }
+    seller_state.record_sell(amount, policy.sell_window, now)?;
 
-        // Ensure seller has enough tokens
+    // Global amount within the current slot
+    if lock_account.current_slot != slot {
+        lock_account.current_slot = slot;
+        lock_account.sold_in_slot = 0;
+    }
+    let sold_in_slot = lock_account
+        .sold_in_slot
+        .checked_add(amount)
+        .ok_or(TradingError::MathOverflow)?;
+    if policy.max_sell_per_slot > 0 {
         require!(
-            ctx.accounts.seller_token_account.amount >= amount,
-            TradingError::InsufficientBalance
+            sold_in_slot <= policy.max_sell_per_slot,
+            TradingError::SlotSellLimitExceeded
         );
+    }
+    lock_account.sold_in_slot = sold_in_slot;
 
-        // Transfer tokens from seller to trading pool
-        token::transfer(
-            ctx.accounts
-                .transfer_context()
-                .with_signer(&[&ctx.accounts.seller.key().as_ref()]),
-            amount,
-        )?;
+    seller_state.last_sell_time = now;
+    seller_state.total_sold = seller_state
+        .total_sold
+        .checked_add(amount)
+        .ok_or(TradingError::MathOverflow)?;
+    seller_state.sell_count += 1;
 
-        // Update trading pool total traded amount
-        trading_pool.total_traded += amount;
+    Ok(())
+}
 
-        msg!(
-            "User {} sold {} tokens. Total traded in pool: {}",
-            ctx.accounts.seller.key(),
-            amount,
-            trading_pool.total_traded
-        );
+/// Constant-product output for `amount_in` against the given reserves, rounded down
+fn get_amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+    require!(
+        reserve_in > 0 && reserve_out > 0,
+        TradingError::InsufficientLiquidity
+    );
 
-        Ok(())
+    let amount_out = (amount_in as u128 * reserve_out as u128)
+        / (reserve_in as u128 + amount_in as u128);
+    require!(amount_out > 0, TradingError::ZeroAmount);
+
+    to_u64(amount_out)
+}
+
+/// Constant-product input needed to take `amount_out` from the given reserves, rounded up
+fn get_amount_in(amount_out: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+    require!(
+        reserve_in > 0 && amount_out < reserve_out,
+        TradingError::InsufficientLiquidity
+    );
+
+    let amount_in = (reserve_in as u128 * amount_out as u128)
+        .div_ceil(reserve_out as u128 - amount_out as u128);
+
+    to_u64(amount_in)
+}
+
+/// Reject a trade submitted after its deadline
+fn require_before_deadline(deadline: i64) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp <= deadline,
+        TradingError::DeadlineExpired
+    );
+    Ok(())
+}
+
+/// Integer square root (floor) by Newton's method
+pub(crate) fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
     }
+    let mut x = value;
+    let mut y = x / 2 + 1;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+fn checked_add(a: u64, b: u64) -> Result<u64> {
+    Ok(a.checked_add(b).ok_or(TradingError::MathOverflow)?)
+}
+
+fn to_u64(value: u128) -> Result<u64> {
+    Ok(u64::try_from(value).map_err(|_| TradingError::MathOverflow)?)
 }
 
-/// **Context for Buying Tokens**
+/// **Context for Initializing a Trading Pool**
 #[derive(Accounts)]
-pub struct BuyTokens<'info> {
+pub struct InitializeTradingPool<'info> {
     #[account(mut)]
-    pub buyer: Signer<'info>,
+    pub authority: Signer<'info>,
 
-    #[account(mut)]
-    pub seller: Signer<'info>,
+    #[account(
+        constraint = crate::bonding_curve::is_launch_authority(
+            &token_mint,
+            bonding_curve.as_deref(),
+            authority.key()
+        ) @ TradingError::Unauthorized,
+        constraint = !crate::bonding_curve::is_curve_minting(&token_mint)
+            @ TradingError::CurveNotGraduated
+    )]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"bonding_curve", token_mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Option<Account<'info, BondingCurve>>,
 
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TradingPool::INIT_SPACE,
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// **Context for Initializing a Liquidity Pool**
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
     #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = !crate::bonding_curve::is_curve_minting(&token_mint)
+            @ TradingError::CurveNotGraduated
+    )]
     pub token_mint: Account<'info, Mint>,
 
-    #[account(mut)]
-    pub seller_token_account: Account<'info, TokenAccount>,
+    #[account(constraint = quote_mint.key() != token_mint.key() @ TradingError::InvalidQuoteMint)]
+    pub quote_mint: Account<'info, Mint>,
 
-    #[account(mut)]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump = trading_pool.bump,
+        constraint = trading_pool.authority == authority.key() @ TradingError::Unauthorized
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
 
     #[account(
-        mut,
-        seeds = [b"trading_pool"],
+        init,
+        payer = authority,
+        space = 8 + LiquidityPool::INIT_SPACE,
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
         bump
     )]
-    pub trading_pool: Account<'info, TradingPool>,
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_token_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = liquidity_pool
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_quote_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = liquidity_pool
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"lp_mint", token_mint.key().as_ref()],
+        bump,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::authority = liquidity_pool
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"protocol_treasury", quote_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = protocol_config
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"creator_fee_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = liquidity_pool
+    )]
+    pub creator_fee_vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
-/// **Context for Selling Tokens**
+/// **Context for Adding Liquidity**
 #[derive(Accounts)]
-pub struct SellTokens<'info> {
+pub struct AddLiquidity<'info> {
     #[account(mut)]
-    pub seller: Signer<'info>,
+    pub provider: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
+        bump = liquidity_pool.bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
 
     #[account(
         mut,
-        seeds = [b"trading_pool"],
+        seeds = [b"pool_token_vault", token_mint.key().as_ref()],
         bump
     )]
-    pub trading_pool: Account<'info, TradingPool>,
+    pub token_vault: Account<'info, TokenAccount>,
 
-    #[account(mut)]
-    pub seller_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"pool_quote_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
 
-    #[account(mut)]
-    pub trading_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = provider_token_account.mint == token_mint.key()
+    )]
+    pub provider_token_account: Account<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [b"governance"],
-        bump
+        constraint = provider_quote_account.mint == liquidity_pool.quote_mint
+    )]
+    pub provider_quote_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = liquidity_pool.lp_mint
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = provider_lp_account.mint == lp_mint.key()
     )]
-    pub governance: Account<'info, Governance>,
+    pub provider_lp_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
 }
 
-/// **Trading Pool Account**
-#[account]
-pub struct TradingPool {
-    pub total_traded: u64,
-}
+/// **Context for Removing Liquidity**
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
 
-/// **Governance Account**
-#[account]
-pub struct Governance {
-    pub selling_locked: bool, // Whether selling is locked
-}
+    pub token_mint: Account<'info, Mint>,
 
-/// **Trading Errors**
-#[error_code]
-pub enum TradingError {
-    #[msg("Insufficient token supply for purchase.")]
-    InsufficientSupply,
-    #[msg("Insufficient balance to sell tokens.")]
-    InsufficientBalance,
-    #[msg("Selling of tokens is currently locked by governance.")]
-    SellingLocked,
-}
+    #[account(
+        mut,
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
+        bump = liquidity_pool.bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
 
-impl<'info> BuyTokens<'info> {
-    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.seller_token_account.to_account_info(),
-            to: self.buyer_token_account.to_account_info(),
-            authority: self.seller.to_account_info(),
+    #[account(
+        mut,
+        seeds = [b"pool_token_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_quote_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = provider_token_account.mint == token_mint.key()
+    )]
+    pub provider_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = provider_quote_account.mint == liquidity_pool.quote_mint
+    )]
+    pub provider_quote_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = liquidity_pool.lp_mint
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = provider_lp_account.mint == lp_mint.key(),
+        constraint = provider_lp_account.owner == provider.key()
+    )]
+    pub provider_lp_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Context for Buying Tokens from the Liquidity Pool**
+#[derive(Accounts)]
+pub struct SwapQuoteForTokens<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump = trading_pool.bump
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
+        bump = liquidity_pool.bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_token_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_quote_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == token_mint.key()
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = buyer_quote_account.mint == liquidity_pool.quote_mint
+    )]
+    pub buyer_quote_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_treasury", liquidity_pool.quote_mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Context for Selling Tokens**
+#[derive(Accounts)]
+pub struct SellTokens<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump = trading_pool.bump
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == token_mint.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = seller_quote_account.mint == liquidity_pool.quote_mint
+    )]
+    pub seller_quote_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_treasury", liquidity_pool.quote_mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
+        bump = liquidity_pool.bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_token_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_quote_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = 8 + SellerState::INIT_SPACE,
+        seeds = [b"seller_state", token_mint.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_state: Account<'info, SellerState>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Context for Reading a Pool TWAP**
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
+        bump = liquidity_pool.bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+}
+
+/// **Context for Initializing an Order Book**
+#[derive(Accounts)]
+pub struct InitializeOrderBook<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(address = liquidity_pool.quote_mint @ TradingError::InvalidQuoteMint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump = trading_pool.bump,
+        constraint = trading_pool.authority == authority.key() @ TradingError::Unauthorized
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        seeds = [b"liquidity_pool", token_mint.key().as_ref()],
+        bump = liquidity_pool.bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + OrderBook::INIT_SPACE,
+        seeds = [b"order_book", token_mint.key().as_ref()],
+        bump
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"book_token_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = order_book
+    )]
+    pub book_token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"book_quote_vault", token_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = order_book
+    )]
+    pub book_quote_vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// **Context for Placing a Limit Order**
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + SellerState::INIT_SPACE,
+        seeds = [b"seller_state", token_mint.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub seller_state: Account<'info, SellerState>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", token_mint.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", token_mint.key().as_ref()],
+        bump = order_book.bump
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Order::INIT_SPACE,
+        seeds = [b"order", order_book.key().as_ref(), &order_book.next_order_id.to_le_bytes()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+
+    #[account(
+        mut,
+        seeds = [b"book_token_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub book_token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"book_quote_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub book_quote_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == token_mint.key()
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_quote_account.mint == order_book.quote_mint
+    )]
+    pub owner_quote_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Context for Cancelling a Limit Order**
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", token_mint.key().as_ref()],
+        bump = order_book.bump
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"order", order_book.key().as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        has_one = owner @ TradingError::NotOrderOwner
+    )]
+    pub order: Account<'info, Order>,
+
+    #[account(
+        mut,
+        seeds = [b"book_token_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub book_token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"book_quote_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub book_quote_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == token_mint.key()
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_quote_account.mint == order_book.quote_mint
+    )]
+    pub owner_quote_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Context for Matching a Bid with an Ask**
+#[derive(Accounts)]
+pub struct MatchOrders<'info> {
+    pub cranker: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"lock_account", token_mint.key().as_ref()],
+        bump = lock_account.bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool", token_mint.key().as_ref()],
+        bump = trading_pool.bump
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", token_mint.key().as_ref()],
+        bump = order_book.bump
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"order", order_book.key().as_ref(), &bid.order_id.to_le_bytes()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, Order>,
+
+    #[account(
+        mut,
+        seeds = [b"order", order_book.key().as_ref(), &ask.order_id.to_le_bytes()],
+        bump = ask.bump
+    )]
+    pub ask: Account<'info, Order>,
+
+    #[account(mut, address = bid.owner)]
+    pub bid_owner: SystemAccount<'info>,
+
+    #[account(mut, address = ask.owner)]
+    pub ask_owner: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = bid_owner_token_account.mint == token_mint.key(),
+        constraint = bid_owner_token_account.owner == bid.owner
+    )]
+    pub bid_owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = bid_owner_quote_account.mint == order_book.quote_mint,
+        constraint = bid_owner_quote_account.owner == bid.owner
+    )]
+    pub bid_owner_quote_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = ask_owner_quote_account.mint == order_book.quote_mint,
+        constraint = ask_owner_quote_account.owner == ask.owner
+    )]
+    pub ask_owner_quote_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"book_token_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub book_token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"book_quote_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub book_quote_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_treasury", order_book.quote_mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// **Trading Pool Account**
+#[account]
+#[derive(InitSpace)]
+pub struct TradingPool {
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,
+    pub total_traded: u64,
+    pub creator_fee_bps: u16,      // Share of every pool trade paid to `authority`
+    pub bump: u8,
+}
+
+/// **Price-Drop Circuit Breaker, set by governance (0 `max_drop_bps` disables it)**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace)]
+pub struct CircuitBreaker {
+    pub max_drop_bps: u16,         // Largest allowed drop of the spot price below the TWAP
+    pub twap_window: i64,          // Period of the TWAP the spot price is compared with (in seconds)
+    pub pause_duration: i64,       // How long sells stay paused once tripped (in seconds)
+}
+
+impl CircuitBreaker {
+    /// Reject settings that cannot be evaluated or would pause sells for too long
+    pub fn validate(&self) -> Result<()> {
+        if self.max_drop_bps == 0 {
+            return Ok(());
+        }
+        require!(
+            self.max_drop_bps <= BPS_DENOMINATOR
+                && self.twap_window > 0
+                && self.twap_window <= MAX_CIRCUIT_BREAKER_WINDOW
+                && self.pause_duration > 0
+                && self.pause_duration <= MAX_CIRCUIT_BREAKER_PAUSE,
+            TradingError::InvalidCircuitBreaker
+        );
+        Ok(())
+    }
+
+    /// The TWAP the pool's spot price has fallen too far below, if the breaker should trip;
+    /// fails while the pool lacks `twap_window` seconds of price history
+    pub fn tripped(&self, liquidity_pool: &LiquidityPool, now: i64) -> Result<Option<u128>> {
+        if self.max_drop_bps == 0 {
+            return Ok(None);
+        }
+        let twap = liquidity_pool.twap(self.twap_window, now)?;
+        let floor = twap * (BPS_DENOMINATOR - self.max_drop_bps) as u128 / BPS_DENOMINATOR as u128;
+        Ok((liquidity_pool.spot_price() < floor).then_some(twap))
+    }
+}
+
+/// **Emitted when the circuit breaker pauses sells**
+#[event]
+pub struct SellingPaused {
+    pub token_mint: Pubkey,
+    pub reason: PauseReason,
+    pub price: u128,
+    pub twap: u128,
+    pub paused_until: i64,
+}
+
+/// **Constant-Product Liquidity Pool (one per mint, paired with a quote mint)**
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityPool {
+    pub token_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,           // Mint of the LP tokens representing pool shares
+    pub token_reserve: u64,        // Launched tokens held in the pool token vault
+    pub quote_reserve: u64,        // Quote tokens held in the pool quote vault
+    pub total_shares: u64,         // LP token supply plus the shares locked in the pool
+    pub lp_fee_bps: u16,           // Share of every swap left in the reserves for LP holders
+    pub accrued_lp_fees: u64,      // Quote collected for LP holders over the pool's lifetime
+    pub price_cumulative: u128,    // Running sum of price * seconds, wrapping on overflow
+    pub last_price_update: i64,    // Timestamp `price_cumulative` was last advanced to
+    #[max_len(MAX_PRICE_OBSERVATIONS)]
+    pub observations: Vec<PriceObservation>, // Oldest first, at least PRICE_OBSERVATION_INTERVAL apart
+    pub bump: u8,
+}
+
+/// **Price cumulative as of a given timestamp**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct PriceObservation {
+    pub timestamp: i64,
+    pub price_cumulative: u128,
+}
+
+impl LiquidityPool {
+    /// Tokens and quote taken for a deposit of up to the given maximums, the shares it adds to
+    /// the pool, and the LP tokens minted for them. The first deposit locks `MINIMUM_LIQUIDITY`
+    /// of its shares in the pool for good
+    pub fn deposit_amounts(
+        &self,
+        max_token_amount: u64,
+        max_quote_amount: u64,
+    ) -> Result<(u64, u64, u64, u64)> {
+        if self.total_shares == 0 {
+            // The first deposit sets the price; shares start at the geometric mean of the deposit
+            let shares = isqrt((max_token_amount as u128) * (max_quote_amount as u128));
+            require!(
+                shares > MINIMUM_LIQUIDITY as u128,
+                TradingError::InsufficientInitialLiquidity
+            );
+            let shares = to_u64(shares)?;
+            return Ok((
+                max_token_amount,
+                max_quote_amount,
+                shares,
+                shares - MINIMUM_LIQUIDITY,
+            ));
+        }
+
+        let token_reserve = self.token_reserve as u128;
+        let quote_reserve = self.quote_reserve as u128;
+        let total_shares = self.total_shares as u128;
+
+        // Use as much of both maximums as the current ratio allows, rounding against the provider
+        let quote_needed = (max_token_amount as u128 * quote_reserve).div_ceil(token_reserve);
+        let (token_amount, quote_amount) = if quote_needed <= max_quote_amount as u128 {
+            (max_token_amount as u128, quote_needed)
+        } else {
+            let token_needed = (max_quote_amount as u128 * token_reserve).div_ceil(quote_reserve);
+            (token_needed, max_quote_amount as u128)
+        };
+        let shares = to_u64(
+            (token_amount * total_shares / token_reserve)
+                .min(quote_amount * total_shares / quote_reserve),
+        )?;
+        require!(shares > 0, TradingError::ZeroAmount);
+        Ok((to_u64(token_amount)?, to_u64(quote_amount)?, shares, shares))
+    }
+
+    /// Tokens and quote paid out for burning `shares`, each rounded down
+    pub fn withdrawal_amounts(&self, shares: u64) -> Result<(u64, u64)> {
+        let total_shares = self.total_shares as u128;
+        Ok((
+            to_u64(shares as u128 * self.token_reserve as u128 / total_shares)?,
+            to_u64(shares as u128 * self.quote_reserve as u128 / total_shares)?,
+        ))
+    }
+
+    /// Current price in quote base units per token base unit, scaled by `PRICE_PRECISION`
+    pub fn spot_price(&self) -> u128 {
+        if self.token_reserve == 0 {
+            return 0;
+        }
+        self.quote_reserve as u128 * PRICE_PRECISION / self.token_reserve as u128
+    }
+
+    /// Price cumulative extended to `now` at the current price
+    pub fn price_cumulative_at(&self, now: i64) -> u128 {
+        let elapsed = (now - self.last_price_update).max(0) as u128;
+        self.price_cumulative
+            .wrapping_add(self.spot_price().wrapping_mul(elapsed))
+    }
+
+    /// Advance the price cumulative to `now`; call before reserves change so the elapsed time is
+    /// credited to the price that held during it. Stores an observation once the interval has passed
+    pub fn record_price(&mut self, now: i64) {
+        self.price_cumulative = self.price_cumulative_at(now);
+        self.last_price_update = now;
+
+        let due = match self.observations.last() {
+            Some(last) => now - last.timestamp >= PRICE_OBSERVATION_INTERVAL,
+            None => true,
         };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+        if due {
+            if self.observations.len() == MAX_PRICE_OBSERVATIONS {
+                self.observations.remove(0);
+            }
+            self.observations.push(PriceObservation {
+                timestamp: now,
+                price_cumulative: self.price_cumulative,
+            });
+        }
+    }
+
+    /// Average price over at least the last `period` seconds, measured from the newest
+    /// observation that is old enough
+    pub fn twap(&self, period: i64, now: i64) -> Result<u128> {
+        require!(period > 0, TradingError::TwapPeriodUnavailable);
+
+        let observation = self
+            .observations
+            .iter()
+            .rev()
+            .find(|observation| observation.timestamp <= now - period)
+            .ok_or(TradingError::TwapPeriodUnavailable)?;
+        let elapsed = (now - observation.timestamp) as u128;
+
+        Ok(self
+            .price_cumulative_at(now)
+            .wrapping_sub(observation.price_cumulative)
+            / elapsed)
+    }
+}
+
+/// **Limit Order Book (one per mint)**
+#[account]
+#[derive(InitSpace)]
+pub struct OrderBook {
+    pub token_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub next_order_id: u64,        // Id assigned to the next placed order
+    #[max_len(MAX_OPEN_ORDERS)]
+    pub bids: Vec<OpenOrder>,      // Resting bids, best first: highest price, then oldest
+    #[max_len(MAX_OPEN_ORDERS)]
+    pub asks: Vec<OpenOrder>,      // Resting asks, best first: lowest price, then oldest
+    pub bump: u8,
+}
+
+/// **Price priority entry of a resting order**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct OpenOrder {
+    pub order_id: u64,
+    pub price: u64,
+}
+
+impl OrderBook {
+    /// Queue a new order behind every order on its side with the same or a better price
+    pub fn insert(&mut self, side: OrderSide, order_id: u64, price: u64) -> Result<()> {
+        let orders = match side {
+            OrderSide::Bid => &mut self.bids,
+            OrderSide::Ask => &mut self.asks,
+        };
+        require!(orders.len() < MAX_OPEN_ORDERS, TradingError::OrderBookFull);
+
+        let position = orders
+            .iter()
+            .position(|open| match side {
+                OrderSide::Bid => open.price < price,
+                OrderSide::Ask => open.price > price,
+            })
+            .unwrap_or(orders.len());
+        orders.insert(position, OpenOrder { order_id, price });
+        Ok(())
+    }
+
+    /// Drop an order that was cancelled or fully filled
+    pub fn remove(&mut self, side: OrderSide, order_id: u64) {
+        let orders = match side {
+            OrderSide::Bid => &mut self.bids,
+            OrderSide::Ask => &mut self.asks,
+        };
+        orders.retain(|open| open.order_id != order_id);
+    }
+
+    /// Id of the order that fills first on `side`
+    pub fn best(&self, side: OrderSide) -> Option<u64> {
+        let orders = match side {
+            OrderSide::Bid => &self.bids,
+            OrderSide::Ask => &self.asks,
+        };
+        orders.first().map(|open| open.order_id)
+    }
+}
+
+/// **Resting Limit Order (one per book and order id)**
+#[account]
+#[derive(InitSpace)]
+pub struct Order {
+    pub order_book: Pubkey,
+    pub owner: Pubkey,
+    pub order_id: u64,
+    pub side: OrderSide,
+    pub price: u64,                // Quote base units per whole token
+    pub amount_remaining: u64,     // Unfilled tokens
+    pub quote_locked: u64,         // Quote still escrowed for a bid
+    pub bump: u8,
+}
+
+/// **Order Side**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum OrderSide {
+    Bid,
+    Ask,
+}
+
+/// **Per-Wallet Sell Tracking (one per mint and seller)**
+#[account]
+#[derive(InitSpace)]
+pub struct SellerState {
+    pub token_mint: Pubkey,
+    pub seller: Pubkey,
+    pub sell_buckets: [SellBucket; SELL_WINDOW_BUCKETS], // Tokens sold per slice of the rolling window
+    pub sell_bucket_len: i64,      // Bucket length the buckets were recorded with
+    pub last_sell_time: i64,       // Timestamp of the seller's most recent sell
+    pub total_sold: u64,           // Cumulative tokens sold by this wallet
+    pub sell_count: u64,           // Number of sells by this wallet
+    pub bump: u8,
+}
+
+/// **Tokens a wallet sold in one slice of its rolling sell window**
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace)]
+pub struct SellBucket {
+    pub start: i64,                // Start of the slice (a multiple of the bucket length)
+    pub amount: u64,
+}
+
+impl SellerState {
+    /// Tokens sold within the `window` seconds ending at `now`. A bucket counts while any part
+    /// of it overlaps the window, so a sell is never dropped before a full window has passed.
+    /// Buckets recorded for a different window length no longer line up and count as empty
+    pub fn sold_within(&self, window: i64, now: i64) -> Result<u64> {
+        let bucket_len = sell_bucket_len(window);
+        if self.sell_bucket_len != bucket_len {
+            return Ok(0);
+        }
+        self.sell_buckets
+            .iter()
+            .filter(|bucket| bucket.start + bucket_len > now - window)
+            .try_fold(0u64, |sold, bucket| checked_add(sold, bucket.amount))
     }
+
+    /// Add a sell of `amount` at `now` to its bucket, reusing the slot of a bucket that has
+    /// left the window. A new window length starts the buckets over
+    pub fn record_sell(&mut self, amount: u64, window: i64, now: i64) -> Result<()> {
+        let bucket_len = sell_bucket_len(window);
+        if self.sell_bucket_len != bucket_len {
+            self.sell_buckets = [SellBucket::default(); SELL_WINDOW_BUCKETS];
+            self.sell_bucket_len = bucket_len;
+        }
+        let start = now - now.rem_euclid(bucket_len);
+        let bucket = &mut self.sell_buckets[(start / bucket_len) as usize % SELL_WINDOW_BUCKETS];
+        if bucket.start != start {
+            *bucket = SellBucket { start, amount: 0 };
+        }
+        bucket.amount = checked_add(bucket.amount, amount)?;
+        Ok(())
+    }
+}
+
+/// Bucket length for a rolling `window`; one bucket fewer than the ring already covers the
+/// whole window, so the bucket a new sell overwrites has always left it
+fn sell_bucket_len(window: i64) -> i64 {
+    (window.max(1) as u64).div_ceil(SELL_WINDOW_BUCKETS as u64 - 1) as i64
+}
+
+/// **Trading Errors (codes 6200-6299)**
+#[error_code(offset = 6200)]
+pub enum TradingError {
+    #[msg("Insufficient balance to sell tokens.")]
+    InsufficientBalance,
+    #[msg("Selling of tokens is currently locked by governance.")]
+    SellingLocked,
+    #[msg("Only the mint authority can initialize the trading pool.")]
+    Unauthorized,
+    #[msg("Sell exceeds the per-wallet limit for the current window.")]
+    WalletSellLimitExceeded,
+    #[msg("Sell exceeds the global limit for the current slot.")]
+    SlotSellLimitExceeded,
+    #[msg("Sell exceeds the allowed share of the wallet's holdings.")]
+    HoldingsSellLimitExceeded,
+    #[msg("Arithmetic overflow in trading calculation.")]
+    MathOverflow,
+    #[msg("Seller must wait for the sell cooldown to elapse.")]
+    SellCooldownActive,
+    #[msg("Quote mint must differ from the launched token mint.")]
+    InvalidQuoteMint,
+    #[msg("Liquidity pool has no reserves to trade against.")]
+    InsufficientLiquidity,
+    #[msg("Amount is too small to trade or deposit.")]
+    ZeroAmount,
+    #[msg("Provider holds fewer LP tokens than requested.")]
+    InsufficientShares,
+    #[msg("First deposit must exceed the minimum locked liquidity.")]
+    InsufficientInitialLiquidity,
+    #[msg("Trade price moved beyond the allowed slippage.")]
+    SlippageExceeded,
+    #[msg("Trade deadline has passed.")]
+    DeadlineExpired,
+    #[msg("Orders must be a bid and an ask.")]
+    InvalidOrderSide,
+    #[msg("Bid price is below the ask price.")]
+    OrdersDoNotCross,
+    #[msg("Only the best bid and the best ask can be matched.")]
+    NotBestOrder,
+    #[msg("Order book holds the maximum number of orders on this side.")]
+    OrderBookFull,
+    #[msg("Only the order's owner can cancel it.")]
+    NotOrderOwner,
+    #[msg("Pool has no price history covering the requested TWAP period.")]
+    TwapPeriodUnavailable,
+    #[msg("Selling is paused by the price-drop circuit breaker.")]
+    SellingPaused,
+    #[msg("Circuit breaker needs a drop of at most 10000 bps, a TWAP window of at most four hours and a pause of at most one day.")]
+    InvalidCircuitBreaker,
+    #[msg("Sell tax takes the whole amount, so no exact quote output is possible.")]
+    SellTaxTooHigh,
+    #[msg("Pools for a bonding-curve token are created when its curve graduates.")]
+    CurveNotGraduated,
 }
 
 impl<'info> SellTokens<'info> {
     fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         let cpi_accounts = Transfer {
             from: self.seller_token_account.to_account_info(),
-            to: self.trading_token_account.to_account_info(),
+            to: self.token_vault.to_account_info(),
+            authority: self.seller.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn withdraw_quote_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.quote_vault.to_account_info(),
+            to: self.seller_quote_account.to_account_info(),
+            authority: self.liquidity_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn protocol_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.quote_vault.to_account_info(),
+            to: self.treasury_vault.to_account_info(),
+            authority: self.liquidity_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn creator_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.quote_vault.to_account_info(),
+            to: self.creator_fee_vault.to_account_info(),
+            authority: self.liquidity_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn tax_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.seller_token_account.to_account_info(),
+            to: self.reward_vault.to_account_info(),
             authority: self.seller.to_account_info(),
         };
         CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
     }
 }
+
+impl<'info> AddLiquidity<'info> {
+    fn deposit_token_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.provider_token_account.to_account_info(),
+            to: self.token_vault.to_account_info(),
+            authority: self.provider.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn deposit_quote_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.provider_quote_account.to_account_info(),
+            to: self.quote_vault.to_account_info(),
+            authority: self.provider.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn mint_lp_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.lp_mint.to_account_info(),
+            to: self.provider_lp_account.to_account_info(),
+            authority: self.liquidity_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> RemoveLiquidity<'info> {
+    fn burn_lp_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.lp_mint.to_account_info(),
+            from: self.provider_lp_account.to_account_info(),
+            authority: self.provider.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn withdraw_token_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.token_vault.to_account_info(),
+            to: self.provider_token_account.to_account_info(),
+            authority: self.liquidity_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn withdraw_quote_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.quote_vault.to_account_info(),
+            to: self.provider_quote_account.to_account_info(),
+            authority: self.liquidity_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> SwapQuoteForTokens<'info> {
+    fn deposit_quote_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.buyer_quote_account.to_account_info(),
+            to: self.quote_vault.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn protocol_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.buyer_quote_account.to_account_info(),
+            to: self.treasury_vault.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn creator_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.buyer_quote_account.to_account_info(),
+            to: self.creator_fee_vault.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn withdraw_token_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.token_vault.to_account_info(),
+            to: self.buyer_token_account.to_account_info(),
+            authority: self.liquidity_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> PlaceOrder<'info> {
+    fn escrow_token_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.owner_token_account.to_account_info(),
+            to: self.book_token_vault.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn escrow_quote_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.owner_quote_account.to_account_info(),
+            to: self.book_quote_vault.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn tax_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.owner_token_account.to_account_info(),
+            to: self.reward_vault.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> CancelOrder<'info> {
+    fn refund_token_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.book_token_vault.to_account_info(),
+            to: self.owner_token_account.to_account_info(),
+            authority: self.order_book.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn refund_quote_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.book_quote_vault.to_account_info(),
+            to: self.owner_quote_account.to_account_info(),
+            authority: self.order_book.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> MatchOrders<'info> {
+    fn deliver_token_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.book_token_vault.to_account_info(),
+            to: self.bid_owner_token_account.to_account_info(),
+            authority: self.order_book.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn pay_ask_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.book_quote_vault.to_account_info(),
+            to: self.ask_owner_quote_account.to_account_info(),
+            authority: self.order_book.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn protocol_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.book_quote_vault.to_account_info(),
+            to: self.treasury_vault.to_account_info(),
+            authority: self.order_book.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn creator_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.book_quote_vault.to_account_info(),
+            to: self.creator_fee_vault.to_account_info(),
+            authority: self.order_book.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn refund_bid_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.book_quote_vault.to_account_info(),
+            to: self.bid_owner_quote_account.to_account_info(),
+            authority: self.order_book.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amount_out_rounds_down() {
+        // 1000 * 10000 / 11000 = 909.09...
+        assert_eq!(get_amount_out(1_000, 10_000, 10_000).unwrap(), 909);
+        assert_eq!(
+            get_amount_out(1, 10_000, 10_000).unwrap_err(),
+            TradingError::ZeroAmount.into()
+        );
+        assert_eq!(
+            get_amount_out(1_000, 0, 10_000).unwrap_err(),
+            TradingError::InsufficientLiquidity.into()
+        );
+    }
+
+    #[test]
+    fn amount_in_rounds_up() {
+        // 10000 * 909 / 9091 = 999.89...
+        assert_eq!(get_amount_in(909, 10_000, 10_000).unwrap(), 1_000);
+        assert_eq!(
+            get_amount_in(10_000, 10_000, 10_000).unwrap_err(),
+            TradingError::InsufficientLiquidity.into()
+        );
+    }
+
+    #[test]
+    fn rounding_favors_the_pool() {
+        let (reserve_in, reserve_out) = (1_234_567, 7_654_321);
+        for amount in [1_000, 12_345, 99_999, 500_000] {
+            // Paying the quoted input always yields at least the requested output
+            let amount_in = get_amount_in(amount, reserve_in, reserve_out).unwrap();
+            assert!(get_amount_out(amount_in, reserve_in, reserve_out).unwrap() >= amount);
+
+            // Requesting the quoted output never costs more than the input it was quoted for
+            let amount_out = get_amount_out(amount, reserve_in, reserve_out).unwrap();
+            assert!(get_amount_in(amount_out, reserve_in, reserve_out).unwrap() <= amount);
+        }
+    }
+
+    fn pool(token_reserve: u64, quote_reserve: u64, now: i64) -> LiquidityPool {
+        LiquidityPool {
+            token_mint: Pubkey::default(),
+            quote_mint: Pubkey::default(),
+            lp_mint: Pubkey::default(),
+            token_reserve,
+            quote_reserve,
+            total_shares: 0,
+            lp_fee_bps: 0,
+            accrued_lp_fees: 0,
+            price_cumulative: 0,
+            last_price_update: now,
+            observations: Vec::new(),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn twap_needs_an_observation_old_enough() {
+        let mut liquidity_pool = pool(1_000, 2_000, 0);
+        assert_eq!(
+            liquidity_pool.twap(600, 1_000).unwrap_err(),
+            TradingError::TwapPeriodUnavailable.into()
+        );
+
+        liquidity_pool.record_price(1_000);
+        assert_eq!(
+            liquidity_pool.twap(600, 1_500).unwrap_err(),
+            TradingError::TwapPeriodUnavailable.into()
+        );
+        assert_eq!(
+            liquidity_pool.twap(0, 1_600).unwrap_err(),
+            TradingError::TwapPeriodUnavailable.into()
+        );
+        assert_eq!(liquidity_pool.twap(600, 1_600).unwrap(), 2 * PRICE_PRECISION);
+    }
+
+    #[test]
+    fn twap_weights_prices_by_time() {
+        let mut liquidity_pool = pool(1_000, 1_000, 0);
+        liquidity_pool.record_price(0);
+
+        // Price 1 for 300 seconds, then 4 for 100 seconds
+        liquidity_pool.record_price(300);
+        liquidity_pool.quote_reserve = 4_000;
+        assert_eq!(liquidity_pool.twap(400, 400).unwrap(), 7 * PRICE_PRECISION / 4);
+
+        // Only the newest observation at least `period` old is used
+        assert_eq!(liquidity_pool.twap(100, 400).unwrap(), 4 * PRICE_PRECISION);
+    }
+
+    fn breaker(twap_window: i64) -> CircuitBreaker {
+        CircuitBreaker {
+            max_drop_bps: 1_000,
+            twap_window,
+            pause_duration: 3_600,
+        }
+    }
+
+    /// A pool priced at 1 for its first 600 seconds, then moved to `quote_reserve` / 1000
+    fn pool_after_drop(quote_reserve: u64) -> LiquidityPool {
+        let mut liquidity_pool = pool(1_000, 1_000, 0);
+        liquidity_pool.record_price(0);
+        liquidity_pool.record_price(600);
+        liquidity_pool.quote_reserve = quote_reserve;
+        liquidity_pool
+    }
+
+    #[test]
+    fn breaker_holds_until_the_drop_exceeds_its_threshold() {
+        // A 10% breaker over a TWAP of 1 trips strictly below a price of 0.9
+        assert_eq!(breaker(600).tripped(&pool_after_drop(950), 600).unwrap(), None);
+        assert_eq!(breaker(600).tripped(&pool_after_drop(900), 600).unwrap(), None);
+        assert_eq!(
+            breaker(600).tripped(&pool_after_drop(899), 600).unwrap(),
+            Some(PRICE_PRECISION)
+        );
+
+        let disabled = CircuitBreaker::default();
+        assert_eq!(disabled.tripped(&pool_after_drop(1), 600).unwrap(), None);
+    }
+
+    #[test]
+    fn breaker_fails_without_enough_price_history() {
+        assert_eq!(
+            breaker(1_200).tripped(&pool_after_drop(950), 600).unwrap_err(),
+            TradingError::TwapPeriodUnavailable.into()
+        );
+    }
+
+    #[test]
+    fn breaker_window_is_bounded_by_the_observation_history() {
+        assert!(breaker(MAX_CIRCUIT_BREAKER_WINDOW).validate().is_ok());
+        assert_eq!(
+            breaker(MAX_CIRCUIT_BREAKER_WINDOW + 1).validate().unwrap_err(),
+            TradingError::InvalidCircuitBreaker.into()
+        );
+    }
+
+    fn book() -> OrderBook {
+        OrderBook {
+            token_mint: Pubkey::default(),
+            quote_mint: Pubkey::default(),
+            next_order_id: 0,
+            bids: Vec::new(),
+            asks: Vec::new(),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn book_ranks_orders_by_price_then_age() {
+        let mut book = book();
+        book.insert(OrderSide::Bid, 0, 100).unwrap();
+        book.insert(OrderSide::Bid, 1, 120).unwrap();
+        book.insert(OrderSide::Bid, 2, 120).unwrap();
+        book.insert(OrderSide::Ask, 3, 130).unwrap();
+        book.insert(OrderSide::Ask, 4, 125).unwrap();
+        book.insert(OrderSide::Ask, 5, 125).unwrap();
+
+        let ids = |orders: &[OpenOrder]| orders.iter().map(|o| o.order_id).collect::<Vec<_>>();
+        assert_eq!(ids(&book.bids), vec![1, 2, 0]);
+        assert_eq!(ids(&book.asks), vec![4, 5, 3]);
+
+        book.remove(OrderSide::Bid, 1);
+        book.remove(OrderSide::Ask, 4);
+        assert_eq!(book.best(OrderSide::Bid), Some(2));
+        assert_eq!(book.best(OrderSide::Ask), Some(5));
+
+        book.remove(OrderSide::Ask, 5);
+        book.remove(OrderSide::Ask, 3);
+        assert_eq!(book.best(OrderSide::Ask), None);
+    }
+
+    #[test]
+    fn book_caps_each_side() {
+        let mut book = book();
+        for id in 0..MAX_OPEN_ORDERS as u64 {
+            book.insert(OrderSide::Ask, id, 100).unwrap();
+        }
+        assert_eq!(
+            book.insert(OrderSide::Ask, MAX_OPEN_ORDERS as u64, 90).unwrap_err(),
+            TradingError::OrderBookFull.into()
+        );
+        assert!(book.insert(OrderSide::Bid, MAX_OPEN_ORDERS as u64, 90).is_ok());
+    }
+
+    fn seller() -> SellerState {
+        SellerState {
+            token_mint: Pubkey::default(),
+            seller: Pubkey::default(),
+            sell_buckets: [SellBucket::default(); SELL_WINDOW_BUCKETS],
+            sell_bucket_len: 0,
+            last_sell_time: 0,
+            total_sold: 0,
+            sell_count: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn sells_count_until_a_full_window_has_passed() {
+        // A 700s window is tracked in 100s buckets
+        let mut seller = seller();
+        seller.record_sell(10, 700, 1_000).unwrap();
+        seller.record_sell(5, 700, 1_050).unwrap();
+        seller.record_sell(20, 700, 1_300).unwrap();
+        assert_eq!(seller.sold_within(700, 1_300).unwrap(), 35);
+
+        // The 1000-1100 bucket overlaps the window until 1800
+        assert_eq!(seller.sold_within(700, 1_799).unwrap(), 35);
+        assert_eq!(seller.sold_within(700, 1_800).unwrap(), 20);
+        assert_eq!(seller.sold_within(700, 2_100).unwrap(), 0);
+    }
+
+    #[test]
+    fn sell_buckets_roll_over_once_they_leave_the_window() {
+        let mut seller = seller();
+        seller.record_sell(10, 700, 1_000).unwrap();
+        // 1800 maps onto the same ring slot as 1000, which is reset rather than added to
+        seller.record_sell(3, 700, 1_800).unwrap();
+        assert_eq!(seller.sold_within(700, 1_800).unwrap(), 3);
+        assert_eq!(seller.sell_buckets.iter().map(|b| b.amount).sum::<u64>(), 3);
+    }
+
+    #[test]
+    fn changing_the_window_restarts_the_buckets() {
+        let mut seller = seller();
+        seller.record_sell(10, 700, 1_000).unwrap();
+        assert_eq!(seller.sold_within(1_400, 1_000).unwrap(), 0);
+
+        seller.record_sell(4, 1_400, 1_000).unwrap();
+        assert_eq!(seller.sell_bucket_len, 200);
+        assert_eq!(seller.sold_within(1_400, 1_000).unwrap(), 4);
+        assert_eq!(seller.sold_within(700, 1_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn exact_quote_sells_pay_out_exactly_the_requested_quote() {
+        let mut liquidity_pool = pool(5_000_000_000, 3_000_000_000, 0);
+        for lp_fee_bps in [0, 30, fees::MAX_FEE_BPS] {
+            liquidity_pool.lp_fee_bps = lp_fee_bps;
+            for (protocol_fee_bps, creator_fee_bps) in
+                [(0, 0), (50, 100), (fees::MAX_FEE_BPS, fees::MAX_FEE_BPS)]
+            {
+                for tax_bps in [0, 250, 9_999] {
+                    for quote_amount in [1, 777, 12_345_678] {
+                        let (amount, tax, gross, trade_fees) = price_exact_quote_sell(
+                            &liquidity_pool,
+                            quote_amount,
+                            tax_bps,
+                            protocol_fee_bps,
+                            creator_fee_bps,
+                        )
+                        .unwrap();
+
+                        // The seller receives exactly the requested quote after fees
+                        assert_eq!(gross - trade_fees.total(), quote_amount);
+                        // The taxed-away tokens buy at least the gross quote from the pool
+                        let proceeds = amount - tax;
+                        let paid = get_amount_out(
+                            proceeds,
+                            liquidity_pool.token_reserve,
+                            liquidity_pool.quote_reserve,
+                        )
+                        .unwrap();
+                        assert!(paid >= gross);
+                        // The tax charged is never below what a plain sell of `amount` pays
+                        let plain_tax = amount as u128 * tax_bps as u128 / BPS_DENOMINATOR as u128;
+                        assert!(tax as u128 >= plain_tax);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exact_quote_sells_need_a_tax_below_the_whole_amount() {
+        let liquidity_pool = pool(1_000_000, 1_000_000, 0);
+        assert_eq!(
+            price_exact_quote_sell(&liquidity_pool, 100, BPS_DENOMINATOR, 0, 0).unwrap_err(),
+            TradingError::SellTaxTooHigh.into()
+        );
+    }
+
+    #[test]
+    fn first_deposit_locks_the_minimum_liquidity() {
+        let liquidity_pool = pool(0, 0, 0);
+        // sqrt(4_000_000 * 1_000_000) = 2_000_000
+        assert_eq!(
+            liquidity_pool.deposit_amounts(4_000_000, 1_000_000).unwrap(),
+            (4_000_000, 1_000_000, 2_000_000, 2_000_000 - MINIMUM_LIQUIDITY)
+        );
+        // sqrt(1_000 * 1_000) leaves nothing above the locked minimum
+        assert_eq!(
+            liquidity_pool.deposit_amounts(1_000, 1_000).unwrap_err(),
+            TradingError::InsufficientInitialLiquidity.into()
+        );
+    }
+
+    #[test]
+    fn later_deposits_follow_the_pool_ratio() {
+        let mut liquidity_pool = pool(3_000, 7_000, 0);
+        liquidity_pool.total_shares = 1_000;
+
+        // 100 tokens need 233.3 quote, rounded up; shares take the smaller of the two ratios
+        assert_eq!(
+            liquidity_pool.deposit_amounts(100, 1_000).unwrap(),
+            (100, 234, 33, 33)
+        );
+        // Quote is the tighter side: 50 quote need 21.4 tokens, rounded up
+        assert_eq!(
+            liquidity_pool.deposit_amounts(1_000, 50).unwrap(),
+            (22, 50, 7, 7)
+        );
+        assert_eq!(
+            liquidity_pool.deposit_amounts(1, 1).unwrap_err(),
+            TradingError::ZeroAmount.into()
+        );
+    }
+
+    #[test]
+    fn withdrawals_pay_out_a_share_of_both_reserves() {
+        let mut liquidity_pool = pool(3_000, 7_001, 0);
+        liquidity_pool.total_shares = 1_000;
+        assert_eq!(liquidity_pool.withdrawal_amounts(1_000).unwrap(), (3_000, 7_001));
+        assert_eq!(liquidity_pool.withdrawal_amounts(333).unwrap(), (999, 2_331));
+    }
+}
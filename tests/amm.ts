@@ -0,0 +1,243 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  DEFAULT_SELL_POLICY,
+  balance,
+  createMint,
+  createTokenAccount,
+  deadline,
+  expectError,
+  launchToken,
+  mintPdas,
+  program,
+  treasuryVault,
+  wallet,
+} from "./helpers";
+
+const SELL_TAX_BPS = 200;
+const CREATOR_FEE_BPS = 100;
+const LP_FEE_BPS = 30;
+const MINIMUM_LIQUIDITY = 1_000;
+
+function bpsOf(amount: BN, bps: number): BN {
+  return amount.muln(bps).divn(10_000);
+}
+
+function amountOut(amountIn: BN, reserveIn: BN, reserveOut: BN): BN {
+  return amountIn.mul(reserveOut).div(reserveIn.add(amountIn));
+}
+
+describe("amm", () => {
+  let mint: PublicKey;
+  let tokenAccount: PublicKey;
+  let quoteMint: PublicKey;
+  let quoteAccount: PublicKey;
+  let lpAccount: PublicKey;
+  let protocolFeeBps: number;
+  let treasury: PublicKey;
+  let pdas: ReturnType<typeof mintPdas>;
+
+  function feesOn(amount: BN) {
+    const protocol = bpsOf(amount, protocolFeeBps);
+    const creator = bpsOf(amount, CREATOR_FEE_BPS);
+    const liquidity = bpsOf(amount, LP_FEE_BPS);
+    const total = protocol.add(creator).add(liquidity);
+    return { protocol, creator, liquidity, total };
+  }
+
+  function swapAccounts() {
+    return {
+      buyer: wallet.publicKey,
+      tokenMint: mint,
+      tradingPool: pdas.tradingPool,
+      liquidityPool: pdas.liquidityPool,
+      tokenVault: pdas.tokenVault,
+      quoteVault: pdas.quoteVault,
+      buyerTokenAccount: tokenAccount,
+      buyerQuoteAccount: quoteAccount,
+      protocolConfig: pdas.protocolConfig,
+      treasuryVault: treasury,
+      creatorFeeVault: pdas.creatorFeeVault,
+    };
+  }
+
+  before(async () => {
+    ({ mint, tokenAccount, pdas } = await launchToken(CREATOR_FEE_BPS, {
+      ...DEFAULT_SELL_POLICY,
+      sellTaxBps: SELL_TAX_BPS,
+    }));
+    ({ mint: quoteMint, tokenAccount: quoteAccount } = await createMint());
+    treasury = treasuryVault(quoteMint);
+    const config = await program.account.protocolConfig.fetch(
+      pdas.protocolConfig
+    );
+    protocolFeeBps = config.protocolFeeBps;
+
+    await program.methods
+      .initializePool(LP_FEE_BPS)
+      .accountsPartial({
+        authority: wallet.publicKey,
+        tokenMint: mint,
+        quoteMint,
+        tradingPool: pdas.tradingPool,
+        liquidityPool: pdas.liquidityPool,
+        tokenVault: pdas.tokenVault,
+        quoteVault: pdas.quoteVault,
+        lpMint: pdas.lpMint,
+        protocolConfig: pdas.protocolConfig,
+        treasuryVault: treasury,
+        creatorFeeVault: pdas.creatorFeeVault,
+      })
+      .rpc();
+    lpAccount = await createTokenAccount(pdas.lpMint, wallet.publicKey);
+  });
+
+  it("seeds the pool at the geometric mean of the deposit", async () => {
+    await program.methods
+      .addLiquidity(new BN(1_000_000_000), new BN(2_000_000_000))
+      .accountsPartial({
+        provider: wallet.publicKey,
+        tokenMint: mint,
+        liquidityPool: pdas.liquidityPool,
+        tokenVault: pdas.tokenVault,
+        quoteVault: pdas.quoteVault,
+        providerTokenAccount: tokenAccount,
+        providerQuoteAccount: quoteAccount,
+        lpMint: pdas.lpMint,
+        providerLpAccount: lpAccount,
+      })
+      .rpc();
+
+    const pool = await program.account.liquidityPool.fetch(pdas.liquidityPool);
+    expect(pool.tokenReserve.toNumber()).to.equal(1_000_000_000);
+    expect(pool.quoteReserve.toNumber()).to.equal(2_000_000_000);
+    // isqrt(1e9 * 2e9)
+    expect(pool.totalShares.toNumber()).to.equal(1_414_213_562);
+    // The first MINIMUM_LIQUIDITY shares stay locked in the pool
+    expect((await balance(lpAccount)).toNumber()).to.equal(
+      1_414_213_562 - MINIMUM_LIQUIDITY
+    );
+    expect((await balance(pdas.tokenVault)).toNumber()).to.equal(1_000_000_000);
+  });
+
+  it("buys at the constant-product price net of fees", async () => {
+    const quoteIn = new BN(10_000_000);
+    const fees = feesOn(quoteIn);
+    const poolBefore = await program.account.liquidityPool.fetch(
+      pdas.liquidityPool
+    );
+    const tokensOut = amountOut(
+      quoteIn.sub(fees.total),
+      poolBefore.quoteReserve,
+      poolBefore.tokenReserve
+    );
+    const tokensBefore = await balance(tokenAccount);
+    const treasuryBefore = await balance(treasury);
+    const creatorBefore = await balance(pdas.creatorFeeVault);
+
+    await program.methods
+      .swapQuoteForTokens(quoteIn, tokensOut, deadline())
+      .accountsPartial(swapAccounts())
+      .rpc();
+
+    expect((await balance(tokenAccount)).sub(tokensBefore).toString()).to.equal(
+      tokensOut.toString()
+    );
+    const pool = await program.account.liquidityPool.fetch(pdas.liquidityPool);
+    // The LP fee stays in the reserves
+    const quoteAdded = quoteIn.sub(fees.total).add(fees.liquidity);
+    expect(pool.quoteReserve.toString()).to.equal(
+      poolBefore.quoteReserve.add(quoteAdded).toString()
+    );
+    expect(pool.accruedLpFees.toString()).to.equal(fees.liquidity.toString());
+    expect(pool.tokenReserve.toString()).to.equal(
+      poolBefore.tokenReserve.sub(tokensOut).toString()
+    );
+    expect(
+      (await balance(treasury)).sub(treasuryBefore).toString()
+    ).to.equal(fees.protocol.toString());
+    expect(
+      (await balance(pdas.creatorFeeVault)).sub(creatorBefore).toString()
+    ).to.equal(fees.creator.toString());
+  });
+
+  it("rejects buys beyond the slippage limit or past the deadline", async () => {
+    const quoteIn = new BN(10_000_000);
+    const pool = await program.account.liquidityPool.fetch(pdas.liquidityPool);
+    const tokensOut = amountOut(
+      quoteIn.sub(feesOn(quoteIn).total),
+      pool.quoteReserve,
+      pool.tokenReserve
+    );
+
+    await expectError(
+      program.methods
+        .swapQuoteForTokens(quoteIn, tokensOut.addn(1), deadline())
+        .accountsPartial(swapAccounts())
+        .rpc(),
+      "SlippageExceeded"
+    );
+    await expectError(
+      program.methods
+        .swapQuoteForTokens(quoteIn, tokensOut, deadline(-60))
+        .accountsPartial(swapAccounts())
+        .rpc(),
+      "DeadlineExpired"
+    );
+  });
+
+  it("sells back into the pool net of the sell tax and fees", async () => {
+    const lockAccount = await program.account.lockAccount.fetch(
+      pdas.lockAccount
+    );
+    expect(lockAccount.sellPolicy.sellTaxBps).to.equal(SELL_TAX_BPS);
+
+    // The tax is taken in tokens before the rest is priced against the pool;
+    // fees then come off the quote paid out
+    const amount = new BN(1_000_000);
+    const tax = bpsOf(amount, SELL_TAX_BPS);
+    const pool = await program.account.liquidityPool.fetch(pdas.liquidityPool);
+    const grossOut = amountOut(
+      amount.sub(tax),
+      pool.tokenReserve,
+      pool.quoteReserve
+    );
+    const quoteOut = grossOut.sub(feesOn(grossOut).total);
+    const quoteBefore = await balance(quoteAccount);
+    const rewardsBefore = await balance(pdas.rewardVault);
+
+    await program.methods
+      .sellTokens(amount, quoteOut, deadline())
+      .accountsPartial({
+        seller: wallet.publicKey,
+        tokenMint: mint,
+        tradingPool: pdas.tradingPool,
+        sellerTokenAccount: tokenAccount,
+        sellerQuoteAccount: quoteAccount,
+        protocolConfig: pdas.protocolConfig,
+        treasuryVault: treasury,
+        creatorFeeVault: pdas.creatorFeeVault,
+        liquidityPool: pdas.liquidityPool,
+        tokenVault: pdas.tokenVault,
+        quoteVault: pdas.quoteVault,
+        lockAccount: pdas.lockAccount,
+        sellerState: pdas.sellerState(wallet.publicKey),
+        stakingPool: pdas.stakingPool,
+        rewardVault: pdas.rewardVault,
+      })
+      .rpc();
+
+    expect((await balance(quoteAccount)).sub(quoteBefore).toString()).to.equal(
+      quoteOut.toString()
+    );
+    expect(
+      (await balance(pdas.rewardVault)).sub(rewardsBefore).toString()
+    ).to.equal(tax.toString());
+    const sellerState = await program.account.sellerState.fetch(
+      pdas.sellerState(wallet.publicKey)
+    );
+    expect(sellerState.totalSold.toString()).to.equal(amount.toString());
+    expect(sellerState.sellCount.toNumber()).to.equal(1);
+  });
+});
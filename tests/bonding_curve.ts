@@ -0,0 +1,219 @@
+import { BN } from "@coral-xyz/anchor";
+import {
+  ComputeBudgetProgram,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+} from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  NATIVE_MINT,
+  balance,
+  createTokenAccount,
+  createWrappedSolAccount,
+  deadline,
+  ensureProtocolConfig,
+  expectError,
+  mintInfo,
+  mintPdas,
+  pda,
+  program,
+  treasuryVault,
+  wallet,
+} from "./helpers";
+
+const DECIMALS = 6;
+const GRADUATION_LAMPORTS = new BN(LAMPORTS_PER_SOL);
+// About 3.7% of the virtual token reserve, costing a little over 1 SOL
+const CURVE_BUY = new BN(40_000_000).mul(new BN(10).pow(new BN(DECIMALS)));
+
+describe("bonding curve", () => {
+  const mintAuthority = pda(Buffer.from("mint_authority"));
+  let mint: PublicKey;
+  let tokenAccount: PublicKey;
+  let pdas: ReturnType<typeof mintPdas>;
+
+  function buyFromCurve(amount: BN) {
+    return program.methods
+      .buyFromCurve(amount, new BN(2 * LAMPORTS_PER_SOL), deadline())
+      .accountsPartial({
+        buyer: wallet.publicKey,
+        mintAuthority,
+        tokenMint: mint,
+        bondingCurve: pdas.bondingCurve,
+        curveVault: pdas.curveVault,
+        buyerTokenAccount: tokenAccount,
+      })
+      .rpc();
+  }
+
+  function graduate() {
+    return program.methods
+      .graduate()
+      .accountsPartial({
+        payer: wallet.publicKey,
+        mintAuthority,
+        tokenMint: mint,
+        quoteMint: NATIVE_MINT,
+        bondingCurve: pdas.bondingCurve,
+        curveVault: pdas.curveVault,
+        tradingPool: pdas.tradingPool,
+        liquidityPool: pdas.liquidityPool,
+        tokenVault: pdas.tokenVault,
+        quoteVault: pdas.quoteVault,
+        lpMint: pdas.lpMint,
+        protocolConfig: pdas.protocolConfig,
+        treasuryVault: treasuryVault(NATIVE_MINT),
+        creatorFeeVault: pdas.creatorFeeVault,
+        lockAccount: pdas.lockAccount,
+        stakingPool: pdas.stakingPool,
+        stakingTokenAccount: pdas.stakingTokenAccount,
+        rewardVault: pdas.rewardVault,
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+      ])
+      .rpc();
+  }
+
+  before(async () => {
+    const mintKeypair = Keypair.generate();
+    mint = mintKeypair.publicKey;
+    pdas = mintPdas(mint);
+    await ensureProtocolConfig();
+
+    await program.methods
+      .createCurveToken(
+        "Curve",
+        "CRV",
+        DECIMALS,
+        {
+          virtualReserves: {
+            virtualSolReserve: new BN(30).mul(new BN(LAMPORTS_PER_SOL)),
+            virtualTokenReserve: new BN(1_073_000_000).mul(
+              new BN(10).pow(new BN(DECIMALS))
+            ),
+          },
+        },
+        { solReserve: { lamports: GRADUATION_LAMPORTS } }
+      )
+      .accountsPartial({
+        creator: wallet.publicKey,
+        mintAuthority,
+        mint,
+        bondingCurve: pdas.bondingCurve,
+        curveVault: pdas.curveVault,
+      })
+      .signers([mintKeypair])
+      .rpc();
+    tokenAccount = await createTokenAccount(mint, wallet.publicKey);
+  });
+
+  it("keeps the pools closed while the curve is minting", async () => {
+    await expectError(
+      program.methods
+        .initializeTradingPool(0)
+        .accountsPartial({
+          authority: wallet.publicKey,
+          tokenMint: mint,
+          bondingCurve: pdas.bondingCurve,
+          tradingPool: pdas.tradingPool,
+        })
+        .rpc(),
+      "CurveNotGraduated"
+    );
+    await expectError(graduate(), "GraduationTargetNotReached");
+  });
+
+  it("graduates into a locked pool once the target is reached", async () => {
+    await buyFromCurve(CURVE_BUY);
+    const curve = await program.account.bondingCurve.fetch(pdas.bondingCurve);
+    expect(curve.supply.toString()).to.equal(CURVE_BUY.toString());
+    expect(curve.solReserve.gte(GRADUATION_LAMPORTS)).to.equal(true);
+
+    await graduate();
+
+    const graduated = await program.account.bondingCurve.fetch(
+      pdas.bondingCurve
+    );
+    expect(graduated.graduated).to.equal(true);
+    expect(graduated.solReserve.toNumber()).to.equal(0);
+    expect((await mintInfo(mint)).mintAuthority).to.equal(null);
+
+    const pool = await program.account.liquidityPool.fetch(pdas.liquidityPool);
+    expect(pool.quoteReserve.toString()).to.equal(curve.solReserve.toString());
+    expect((await balance(pdas.quoteVault)).toString()).to.equal(
+      curve.solReserve.toString()
+    );
+    expect((await balance(pdas.tokenVault)).toString()).to.equal(
+      pool.tokenReserve.toString()
+    );
+    // No LP tokens exist, so the seeded liquidity can never be withdrawn
+    expect(pool.totalShares.gtn(0)).to.equal(true);
+    expect((await mintInfo(pdas.lpMint)).supply.toString()).to.equal("0");
+
+    // Sells need the lock account and staking pool, which graduation created
+    const lockAccount = await program.account.lockAccount.fetch(
+      pdas.lockAccount
+    );
+    expect(lockAccount.tokenMint.toBase58()).to.equal(mint.toBase58());
+    const stakingPool = await program.account.stakingPool.fetch(
+      pdas.stakingPool
+    );
+    expect(stakingPool.authority.toBase58()).to.equal(
+      wallet.publicKey.toBase58()
+    );
+
+    await expectError(buyFromCurve(new BN(1_000_000)), "CurveGraduated");
+  });
+
+  it("lets holders buy from and sell into the graduated pool", async () => {
+    const quoteAccount = await createWrappedSolAccount(LAMPORTS_PER_SOL / 10);
+
+    const tokensBefore = await balance(tokenAccount);
+    await program.methods
+      .swapQuoteForTokens(
+        new BN(LAMPORTS_PER_SOL / 100),
+        new BN(1),
+        deadline()
+      )
+      .accountsPartial({
+        buyer: wallet.publicKey,
+        tokenMint: mint,
+        tradingPool: pdas.tradingPool,
+        liquidityPool: pdas.liquidityPool,
+        tokenVault: pdas.tokenVault,
+        quoteVault: pdas.quoteVault,
+        buyerTokenAccount: tokenAccount,
+        buyerQuoteAccount: quoteAccount,
+        protocolConfig: pdas.protocolConfig,
+        treasuryVault: treasuryVault(NATIVE_MINT),
+        creatorFeeVault: pdas.creatorFeeVault,
+      })
+      .rpc();
+    expect((await balance(tokenAccount)).gt(tokensBefore)).to.equal(true);
+
+    const quoteBefore = await balance(quoteAccount);
+    await program.methods
+      .sellTokens(CURVE_BUY.divn(10), new BN(1), deadline())
+      .accountsPartial({
+        seller: wallet.publicKey,
+        tokenMint: mint,
+        tradingPool: pdas.tradingPool,
+        sellerTokenAccount: tokenAccount,
+        sellerQuoteAccount: quoteAccount,
+        protocolConfig: pdas.protocolConfig,
+        treasuryVault: treasuryVault(NATIVE_MINT),
+        creatorFeeVault: pdas.creatorFeeVault,
+        liquidityPool: pdas.liquidityPool,
+        tokenVault: pdas.tokenVault,
+        quoteVault: pdas.quoteVault,
+        lockAccount: pdas.lockAccount,
+        sellerState: pdas.sellerState(wallet.publicKey),
+        stakingPool: pdas.stakingPool,
+        rewardVault: pdas.rewardVault,
+      })
+      .rpc();
+    expect((await balance(quoteAccount)).gt(quoteBefore)).to.equal(true);
+  });
+});
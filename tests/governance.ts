@@ -0,0 +1,172 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  WEEK,
+  expectError,
+  fundTokenAccount,
+  fundedKeypair,
+  launchToken,
+  mintPdas,
+  pda,
+  program,
+  u64Le,
+  waitForNextSlot,
+  waitUntil,
+  wallet,
+} from "./helpers";
+
+const STAKE = new BN(100_000_000);
+const VOTING_PERIOD = 10;
+
+describe("governance", () => {
+  let mint: PublicKey;
+  let tokenAccount: PublicKey;
+  let pdas: ReturnType<typeof mintPdas>;
+
+  async function stake(user: Keypair | null, userTokenAccount: PublicKey) {
+    const owner = user === null ? wallet.publicKey : user.publicKey;
+    await program.methods
+      .stake(STAKE)
+      .accountsPartial({
+        user: owner,
+        tokenMint: mint,
+        stakingPool: pdas.stakingPool,
+        userTokenAccount,
+        stakingTokenAccount: pdas.stakingTokenAccount,
+        stakerAccount: pdas.stakerAccount(owner),
+      })
+      .signers(user === null ? [] : [user])
+      .rpc();
+  }
+
+  async function createProposal(
+    kind: Parameters<typeof program.methods.createProposal>[0],
+    votingPeriod: number
+  ): Promise<PublicKey> {
+    const governance = await program.account.governance.fetch(
+      pdas.governanceAccount
+    );
+    const proposal = pda(
+      Buffer.from("proposal"),
+      pdas.governanceAccount,
+      u64Le(governance.proposalCount)
+    );
+    await program.methods
+      .createProposal(kind, new BN(0), new BN(votingPeriod))
+      .accountsPartial({
+        proposer: wallet.publicKey,
+        tokenMint: mint,
+        governanceAccount: pdas.governanceAccount,
+        stakingPool: pdas.stakingPool,
+        stakerAccount: pdas.stakerAccount(wallet.publicKey),
+        lockAccount: pdas.lockAccount,
+        proposal,
+      })
+      .rpc();
+    return proposal;
+  }
+
+  function castVote(proposal: PublicKey, voter: Keypair | null) {
+    const owner = voter === null ? wallet.publicKey : voter.publicKey;
+    return program.methods
+      .castVote(true)
+      .accountsPartial({
+        voter: owner,
+        tokenMint: mint,
+        governanceAccount: pdas.governanceAccount,
+        proposal,
+        lockAccount: pdas.lockAccount,
+        stakingPool: pdas.stakingPool,
+        stakerAccount: pdas.stakerAccount(owner),
+        voteRecord: pda(Buffer.from("vote_record"), proposal, owner),
+      })
+      .signers(voter === null ? [] : [voter])
+      .rpc();
+  }
+
+  function finalize(proposal: PublicKey) {
+    return program.methods
+      .finalizeProposal()
+      .accountsPartial({
+        tokenMint: mint,
+        governanceAccount: pdas.governanceAccount,
+        proposal,
+        lockAccount: pdas.lockAccount,
+      })
+      .rpc();
+  }
+
+  before(async () => {
+    ({ mint, tokenAccount, pdas } = await launchToken());
+    await stake(null, tokenAccount);
+    await waitForNextSlot();
+  });
+
+  it("rejects voting windows outside the governance bounds", async () => {
+    await expectError(
+      createProposal({ lockSelling: { durationWeeks: 1 } }, 0),
+      "InvalidVotingWindow"
+    );
+    await expectError(
+      createProposal({ lockSelling: { durationWeeks: 1 } }, WEEK + 1),
+      "InvalidVotingWindow"
+    );
+  });
+
+  it("passes a lock proposal with snapshot-weighted votes", async () => {
+    // Funded up front so the late stake below lands well inside the voting window
+    const lateStaker = await fundedKeypair();
+    const lateTokenAccount = await fundTokenAccount(
+      mint,
+      lateStaker.publicKey,
+      STAKE
+    );
+
+    const proposal = await createProposal(
+      { lockSelling: { durationWeeks: 1 } },
+      VOTING_PERIOD
+    );
+    let state = await program.account.proposal.fetch(proposal);
+    expect(state.state).to.have.property("active");
+    const lockAccount = await program.account.lockAccount.fetch(
+      pdas.lockAccount
+    );
+    expect(lockAccount.lockVotePendingUntil.toString()).to.equal(
+      state.votingEndsAt.toString()
+    );
+
+    await castVote(proposal, null);
+    state = await program.account.proposal.fetch(proposal);
+    expect(state.votesInFavor.toString()).to.equal(STAKE.toString());
+    await expectError(castVote(proposal, null), "AlreadyVoted");
+
+    // Stake added after the snapshot carries no voting power on this proposal
+    await stake(lateStaker, lateTokenAccount);
+    await expectError(castVote(proposal, lateStaker), "NoVotingPower");
+
+    await expectError(finalize(proposal), "VotingStillOpen");
+    await waitUntil(state.votingEndsAt.toNumber());
+    await finalize(proposal);
+    state = await program.account.proposal.fetch(proposal);
+    expect(state.state).to.have.property("succeeded");
+    // The decided vote no longer escalates the sell tax
+    const decided = await program.account.lockAccount.fetch(pdas.lockAccount);
+    expect(decided.lockVotePendingUntil.toNumber()).to.equal(0);
+
+    await program.methods
+      .lockSelling()
+      .accountsPartial({
+        tokenMint: mint,
+        governanceAccount: pdas.governanceAccount,
+        proposal,
+        lockAccount: pdas.lockAccount,
+      })
+      .rpc();
+    const locked = await program.account.lockAccount.fetch(pdas.lockAccount);
+    expect(locked.isLocked).to.equal(true);
+    expect(locked.lockDuration.toNumber()).to.equal(WEEK);
+    state = await program.account.proposal.fetch(proposal);
+    expect(state.state).to.have.property("executed");
+  });
+});
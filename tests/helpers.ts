@@ -0,0 +1,328 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorError, BN, Program } from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  ParsedAccountData,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { expect } from "chai";
+import { UpOnly } from "../target/types/up_only";
+
+const { ASSOCIATED_PROGRAM_ID, TOKEN_PROGRAM_ID, associatedAddress } =
+  anchor.utils.token;
+
+export const DAY = 86_400;
+export const WEEK = 7 * DAY;
+export const PROTOCOL_FEE_BPS = 50;
+export const NATIVE_MINT = new PublicKey(
+  "So11111111111111111111111111111111111111112"
+);
+
+export const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+export const program = anchor.workspace.UpOnly as Program<UpOnly>;
+export const wallet = provider.wallet as anchor.Wallet;
+export const connection = provider.connection;
+
+export function u64Le(value: number | BN): Buffer {
+  return new BN(value).toArrayLike(Buffer, "le", 8);
+}
+
+export function pda(...seeds: (Buffer | PublicKey)[]): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    seeds.map((seed) => (seed instanceof PublicKey ? seed.toBuffer() : seed)),
+    program.programId
+  )[0];
+}
+
+// Every per-mint PDA of a launched token
+export function mintPdas(mint: PublicKey) {
+  const stakingPool = pda(Buffer.from("staking_pool"), mint);
+  return {
+    bondingCurve: pda(Buffer.from("bonding_curve"), mint),
+    curveVault: pda(Buffer.from("curve_vault"), mint),
+    governanceAccount: pda(Buffer.from("governance"), mint),
+    lockAccount: pda(Buffer.from("lock_account"), mint),
+    stakingPool,
+    stakingTokenAccount: pda(Buffer.from("staking_vault"), mint),
+    rewardVault: pda(Buffer.from("reward_vault"), mint),
+    tradingPool: pda(Buffer.from("trading_pool"), mint),
+    liquidityPool: pda(Buffer.from("liquidity_pool"), mint),
+    tokenVault: pda(Buffer.from("pool_token_vault"), mint),
+    quoteVault: pda(Buffer.from("pool_quote_vault"), mint),
+    lpMint: pda(Buffer.from("lp_mint"), mint),
+    creatorFeeVault: pda(Buffer.from("creator_fee_vault"), mint),
+    protocolConfig: pda(Buffer.from("protocol_config")),
+    stakerAccount: (user: PublicKey) =>
+      pda(Buffer.from("staker"), stakingPool, user),
+    sellerState: (seller: PublicKey) =>
+      pda(Buffer.from("seller_state"), mint, seller),
+  };
+}
+
+// Protocol fee treasury for one quote mint, shared by every pool quoted in it
+export function treasuryVault(quoteMint: PublicKey): PublicKey {
+  return pda(Buffer.from("protocol_treasury"), quoteMint);
+}
+
+export async function balance(tokenAccount: PublicKey): Promise<BN> {
+  const { value } = await connection.getTokenAccountBalance(tokenAccount);
+  return new BN(value.amount);
+}
+
+// Decimals, supply and mint authority of an SPL mint
+export async function mintInfo(mint: PublicKey) {
+  const { value } = await connection.getParsedAccountInfo(mint);
+  const { info } = (value.data as ParsedAccountData).parsed;
+  return {
+    decimals: info.decimals as number,
+    supply: new BN(info.supply),
+    mintAuthority:
+      info.mintAuthority === null ? null : new PublicKey(info.mintAuthority),
+  };
+}
+
+export function sleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+// Wait until the cluster clock reaches `timestamp`
+export async function waitUntil(timestamp: number): Promise<void> {
+  for (;;) {
+    const time = await connection.getBlockTime(await connection.getSlot());
+    if (time !== null && time >= timestamp) {
+      return;
+    }
+    await sleep(500);
+  }
+}
+
+// Wait for a later slot, so checkpoints recorded so far fall before the next snapshot
+export async function waitForNextSlot(): Promise<void> {
+  const slot = await connection.getSlot();
+  while ((await connection.getSlot()) <= slot) {
+    await sleep(100);
+  }
+}
+
+export function deadline(seconds = 60): BN {
+  return new BN(Math.floor(Date.now() / 1000) + seconds);
+}
+
+export async function fundedKeypair(sol = 2): Promise<Keypair> {
+  const keypair = Keypair.generate();
+  const signature = await connection.requestAirdrop(
+    keypair.publicKey,
+    sol * LAMPORTS_PER_SOL
+  );
+  await connection.confirmTransaction(signature, "confirmed");
+  return keypair;
+}
+
+// Assert that `promise` fails with the program error named `code`
+export async function expectError(
+  promise: Promise<unknown>,
+  code: string
+): Promise<void> {
+  try {
+    await promise;
+  } catch (err) {
+    expect(err).to.be.instanceOf(AnchorError);
+    expect((err as AnchorError).error.errorCode.code).to.equal(code);
+    return;
+  }
+  expect.fail(`expected the transaction to fail with ${code}`);
+}
+
+// Create the protocol config on first use; it is shared by every test file
+export async function ensureProtocolConfig(): Promise<PublicKey> {
+  const protocolConfig = pda(Buffer.from("protocol_config"));
+  const existing = await program.account.protocolConfig.fetchNullable(
+    protocolConfig
+  );
+  if (existing === null) {
+    await program.methods
+      .initializeProtocolConfig(PROTOCOL_FEE_BPS)
+      .accountsPartial({ admin: wallet.publicKey, protocolConfig })
+      .rpc();
+  }
+  return protocolConfig;
+}
+
+// Create the associated token account of `owner` for `mint`, paid for by the wallet
+export async function createTokenAccount(
+  mint: PublicKey,
+  owner: PublicKey
+): Promise<PublicKey> {
+  const tokenAccount = associatedAddress({ mint, owner });
+  await provider.sendAndConfirm(
+    new Transaction().add(
+      new TransactionInstruction({
+        programId: ASSOCIATED_PROGRAM_ID,
+        keys: [
+          { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
+          { pubkey: tokenAccount, isSigner: false, isWritable: true },
+          { pubkey: owner, isSigner: false, isWritable: false },
+          { pubkey: mint, isSigner: false, isWritable: false },
+          {
+            pubkey: SystemProgram.programId,
+            isSigner: false,
+            isWritable: false,
+          },
+          { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ],
+        data: Buffer.alloc(0),
+      })
+    )
+  );
+  return tokenAccount;
+}
+
+// Create the wallet's wrapped SOL account holding `lamports`
+export async function createWrappedSolAccount(
+  lamports: number
+): Promise<PublicKey> {
+  const tokenAccount = await createTokenAccount(NATIVE_MINT, wallet.publicKey);
+  await provider.sendAndConfirm(
+    new Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: wallet.publicKey,
+        toPubkey: tokenAccount,
+        lamports,
+      }),
+      // SyncNative, so the token balance reflects the lamports just sent
+      new TransactionInstruction({
+        programId: TOKEN_PROGRAM_ID,
+        keys: [{ pubkey: tokenAccount, isSigner: false, isWritable: true }],
+        data: Buffer.from([17]),
+      })
+    )
+  );
+  return tokenAccount;
+}
+
+// Create a plain SPL mint through `create_token` and mint `supply` to a new wallet account
+export async function createMint(
+  decimals = 6,
+  supply: BN = new BN(1_000_000_000_000)
+): Promise<{ mint: PublicKey; tokenAccount: PublicKey }> {
+  const mint = Keypair.generate();
+  await program.methods
+    .createToken("Up Only", "UP", decimals)
+    .accountsPartial({ authority: wallet.publicKey, mint: mint.publicKey })
+    .signers([mint])
+    .rpc();
+
+  const tokenAccount = await createTokenAccount(
+    mint.publicKey,
+    wallet.publicKey
+  );
+  await program.methods
+    .mintTokens(supply)
+    .accountsPartial({
+      mintAuthority: wallet.publicKey,
+      mint: mint.publicKey,
+      tokenAccount,
+    })
+    .rpc();
+
+  return { mint: mint.publicKey, tokenAccount };
+}
+
+export const DEFAULT_SELL_POLICY = {
+  maxSellPerWindow: new BN(0),
+  sellWindow: new BN(0),
+  maxSellPerSlot: new BN(0),
+  maxSellBpsOfHoldings: 0,
+  sellCooldown: new BN(0),
+  sellTaxBps: 0,
+  escalatedSellTaxBps: 0,
+};
+
+export const DEFAULT_VOTING_RULES = {
+  minVoteStakeDuration: new BN(0),
+  minProposalStake: new BN(1),
+  maxVotingDelay: new BN(DAY),
+  maxVotingPeriod: new BN(WEEK),
+};
+
+// Launch a token the way its creator would: mint it, then set up staking, governance, the
+// lock account and the trading pool
+export async function launchToken(
+  creatorFeeBps = 0,
+  sellPolicy = DEFAULT_SELL_POLICY
+) {
+  await ensureProtocolConfig();
+  const { mint, tokenAccount } = await createMint();
+  const pdas = mintPdas(mint);
+  const authority = wallet.publicKey;
+
+  await program.methods
+    .initializeStakingPool(new BN(0))
+    .accountsPartial({
+      authority,
+      tokenMint: mint,
+      bondingCurve: null,
+      stakingPool: pdas.stakingPool,
+      stakingTokenAccount: pdas.stakingTokenAccount,
+      rewardVault: pdas.rewardVault,
+    })
+    .rpc();
+  await program.methods
+    .initializeGovernance(
+      6_000,
+      2_000,
+      { stakedSupply: {} },
+      new BN(WEEK),
+      new BN(4 * WEEK),
+      DEFAULT_VOTING_RULES
+    )
+    .accountsPartial({
+      authority,
+      tokenMint: mint,
+      bondingCurve: null,
+      governanceAccount: pdas.governanceAccount,
+    })
+    .rpc();
+  await program.methods
+    .initializeLockAccount(sellPolicy)
+    .accountsPartial({
+      authority,
+      tokenMint: mint,
+      bondingCurve: null,
+      lockAccount: pdas.lockAccount,
+    })
+    .rpc();
+  await program.methods
+    .initializeTradingPool(creatorFeeBps)
+    .accountsPartial({
+      authority,
+      tokenMint: mint,
+      bondingCurve: null,
+      tradingPool: pdas.tradingPool,
+    })
+    .rpc();
+
+  return { mint, tokenAccount, pdas };
+}
+
+// Create a token account of `owner` for a mint the wallet controls and mint `amount` to it
+export async function fundTokenAccount(
+  mint: PublicKey,
+  owner: PublicKey,
+  amount: BN
+): Promise<PublicKey> {
+  const tokenAccount = await createTokenAccount(mint, owner);
+  if (!amount.isZero()) {
+    await program.methods
+      .mintTokens(amount)
+      .accountsPartial({ mintAuthority: wallet.publicKey, mint, tokenAccount })
+      .rpc();
+  }
+  return tokenAccount;
+}
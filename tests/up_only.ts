@@ -1,16 +1,117 @@
-import * as anchor from "@coral-xyz/anchor";
-import { Program } from "@coral-xyz/anchor";
-import { UpOnly } from "../target/types/up_only";
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  DEFAULT_SELL_POLICY,
+  PROTOCOL_FEE_BPS,
+  WEEK,
+  balance,
+  createMint,
+  ensureProtocolConfig,
+  expectError,
+  fundedKeypair,
+  launchToken,
+  mintInfo,
+  mintPdas,
+  program,
+  wallet,
+} from "./helpers";
 
 describe("up_only", () => {
-  // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  it("initializes the protocol config once", async () => {
+    const protocolConfig = await ensureProtocolConfig();
 
-  const program = anchor.workspace.UpOnly as Program<UpOnly>;
+    const config = await program.account.protocolConfig.fetch(protocolConfig);
+    expect(config.admin.toBase58()).to.equal(wallet.publicKey.toBase58());
+    expect(config.protocolFeeBps).to.equal(PROTOCOL_FEE_BPS);
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+    await expectError(
+      program.methods
+        .setProtocolFee(1_001)
+        .accountsPartial({ admin: wallet.publicKey, protocolConfig })
+        .rpc(),
+      "FeeTooHigh"
+    );
+  });
+
+  it("creates a token and mints its supply to a holder", async () => {
+    const supply = new BN(5_000_000);
+    const { mint, tokenAccount } = await createMint(9, supply);
+
+    const info = await mintInfo(mint);
+    expect(info.decimals).to.equal(9);
+    expect(info.supply.toString()).to.equal(supply.toString());
+    expect(info.mintAuthority.toBase58()).to.equal(wallet.publicKey.toBase58());
+    expect((await balance(tokenAccount)).toString()).to.equal(
+      supply.toString()
+    );
+  });
+
+  it("bootstraps every per-mint account of a launched token", async () => {
+    const { mint, pdas } = await launchToken(100);
+
+    const stakingPool = await program.account.stakingPool.fetch(
+      pdas.stakingPool
+    );
+    expect(stakingPool.tokenMint.toBase58()).to.equal(mint.toBase58());
+    expect(stakingPool.totalStaked.toNumber()).to.equal(0);
+
+    const governance = await program.account.governance.fetch(
+      pdas.governanceAccount
+    );
+    expect(governance.tokenMint.toBase58()).to.equal(mint.toBase58());
+    expect(governance.approvalThresholdBps).to.equal(6_000);
+    expect(governance.quorumThresholdBps).to.equal(2_000);
+    expect(governance.minLockTime.toNumber()).to.equal(WEEK);
+    expect(governance.maxLockTime.toNumber()).to.equal(4 * WEEK);
+
+    const lockAccount = await program.account.lockAccount.fetch(
+      pdas.lockAccount
+    );
+    expect(lockAccount.isLocked).to.equal(false);
+    expect(lockAccount.sellPolicy.sellTaxBps).to.equal(0);
+
+    const tradingPool = await program.account.tradingPool.fetch(
+      pdas.tradingPool
+    );
+    expect(tradingPool.authority.toBase58()).to.equal(
+      wallet.publicKey.toBase58()
+    );
+    expect(tradingPool.creatorFeeBps).to.equal(100);
+    expect(tradingPool.totalTraded.toNumber()).to.equal(0);
+  });
+
+  it("rejects setup by a wallet that is not the mint authority", async () => {
+    const { mint } = await createMint();
+    const pdas = mintPdas(mint);
+    const stranger = await fundedKeypair();
+
+    await expectError(
+      program.methods
+        .initializeLockAccount(DEFAULT_SELL_POLICY)
+        .accountsPartial({
+          authority: stranger.publicKey,
+          tokenMint: mint,
+          bondingCurve: null,
+          lockAccount: pdas.lockAccount,
+        })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+    await expectError(
+      program.methods
+        .initializeStakingPool(new BN(0))
+        .accountsPartial({
+          authority: stranger.publicKey,
+          tokenMint: mint,
+          bondingCurve: null,
+          stakingPool: pdas.stakingPool,
+          stakingTokenAccount: pdas.stakingTokenAccount,
+          rewardVault: pdas.rewardVault,
+        })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
   });
 });